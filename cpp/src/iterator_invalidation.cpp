@@ -0,0 +1,16 @@
+#include <iostream>
+#include <vector>
+
+int main() {
+    std::vector<int> numbers = {1, 2, 3};
+
+    for (auto it = numbers.begin(); it != numbers.end(); ++it) {
+        if (*it == 2) {
+            numbers.push_back(4); // the vector is full and moves its elements, it points into the freed memory
+        }
+
+        std::cout << *it << std::endl;
+    }
+
+    return 0;
+}
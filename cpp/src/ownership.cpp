@@ -0,0 +1,29 @@
+#include <iostream>
+#include <memory>
+
+struct Atom {
+    const char* name;
+
+    explicit Atom(const char* name) : name(name) {}
+
+    ~Atom() {
+        std::cout << "destroy " << name << std::endl;
+    }
+};
+
+int main() {
+    std::unique_ptr<Atom> hydrogen = std::make_unique<Atom>("H");
+    std::unique_ptr<Atom> owner = std::move(hydrogen); // unique_ptr can only be moved, not copied
+
+    std::shared_ptr<Atom> oxygen = std::make_shared<Atom>("O");
+
+    {
+        std::shared_ptr<Atom> shared = oxygen; // a copy increments the reference count
+
+        std::cout << "owners of O: " << oxygen.use_count() << std::endl;
+    }
+
+    std::cout << "owners of O: " << oxygen.use_count() << std::endl;
+
+    return 0; // destroyed in reverse order: oxygen, then owner
+}
@@ -0,0 +1,15 @@
+#include <iostream>
+#include <string>
+#include <string_view>
+
+std::string describe(const char* element) {
+    return std::string(element) + " is the first element of the periodic table";
+}
+
+int main() {
+    std::string_view view = describe("hydrogen"); // the string is destroyed at the end of the statement
+
+    std::cout << view << std::endl; // reading the freed memory of the string
+
+    return 0;
+}
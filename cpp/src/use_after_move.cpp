@@ -0,0 +1,17 @@
+#include <iostream>
+#include <memory>
+#include <string>
+
+int main() {
+    std::string name = "hydrogen";
+    std::string moved = std::move(name); // name is still usable, in a "valid but unspecified" state
+
+    std::cout << "name: '" << name << "', moved: '" << moved << "'" << std::endl;
+
+    std::unique_ptr<int> number = std::make_unique<int>(1);
+    std::unique_ptr<int> owner = std::move(number); // number is now a null pointer
+
+    std::cout << *number << std::endl; // dereferencing a null pointer
+
+    return 0;
+}
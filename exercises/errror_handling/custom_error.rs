@@ -0,0 +1,54 @@
+//! Lesson: `snippets/src/errror_handling.rs` (`error_handling`, `question_mark_operator`)
+//!
+//! `parse_element` uses `?` on two different errors, which only works if both can be converted into `ElementError`.
+//! Implement the two `From` conversions and `Display` (the `todo!()`s).
+
+use std::fmt;
+use std::num::{ParseFloatError, ParseIntError};
+
+#[derive(Debug, PartialEq)]
+enum ElementError {
+    Number(ParseIntError),
+    Weight(ParseFloatError),
+}
+
+impl From<ParseIntError> for ElementError {
+    fn from(error: ParseIntError) -> Self {
+        todo!("{error}")
+    }
+}
+
+impl From<ParseFloatError> for ElementError {
+    fn from(error: ParseFloatError) -> Self {
+        todo!("{error}")
+    }
+}
+
+impl fmt::Display for ElementError {
+    /// `invalid atomic number: <error>` or `invalid weight: <error>`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        todo!()
+    }
+}
+
+/// Parses `"<atomic number> <weight>"`, e.g. `"26 55.845"`.
+fn parse_element(line: &str) -> Result<(u32, f32), ElementError> {
+    let (number, weight) = line.split_once(' ').unwrap_or((line, ""));
+
+    Ok((number.parse()?, weight.parse()?))
+}
+
+#[test]
+fn custom_error() {
+    assert_eq!(parse_element("26 55.845"), Ok((26, 55.845)));
+
+    let error = parse_element("iron 55.845").unwrap_err();
+
+    assert!(matches!(error, ElementError::Number(_)));
+    assert_eq!(error.to_string(), "invalid atomic number: invalid digit found in string");
+
+    let error = parse_element("26 heavy").unwrap_err();
+
+    assert!(matches!(error, ElementError::Weight(_)));
+    assert_eq!(error.to_string(), "invalid weight: invalid float literal");
+}
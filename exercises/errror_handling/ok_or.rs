@@ -0,0 +1,20 @@
+//! Lesson: `snippets/src/errror_handling.rs` (`error_handling`)
+//!
+//! `weight` doesn't compile: `get` returns an `Option`, but the function returns a `Result`.
+//! Turn the `Option` into a `Result` with the error message `"unknown element: <symbol>"` and use `?`.
+
+use std::collections::HashMap;
+
+fn weight(weights: &HashMap<&str, f32>, symbol: &str) -> Result<f32, String> {
+    let weight = weights.get(symbol)?;
+
+    Ok(*weight)
+}
+
+#[test]
+fn ok_or() {
+    let weights = HashMap::from([("Fe", 55.845), ("Cu", 63.546)]);
+
+    assert_eq!(weight(&weights, "Fe"), Ok(55.845));
+    assert_eq!(weight(&weights, "Xx"), Err("unknown element: Xx".to_string()));
+}
@@ -0,0 +1,23 @@
+//! Lesson: `snippets/src/errror_handling.rs` (`question_mark_operator`)
+//!
+//! `molar_mass` panics on invalid input instead of returning the error. Replace the `unwrap`s with `?`.
+
+use std::num::ParseFloatError;
+
+/// The mass of a molecule made of two elements, e.g. `molar_mass("1.008", 2, "15.999", 1)` for water.
+fn molar_mass(a: &str, count_a: u32, b: &str, count_b: u32) -> Result<f32, ParseFloatError> {
+    let a: f32 = a.parse().unwrap();
+    let b: f32 = b.parse().unwrap();
+
+    Ok(a * count_a as f32 + b * count_b as f32)
+}
+
+#[test]
+fn question_mark() {
+    let water = molar_mass("1.008", 2, "15.999", 1);
+
+    assert!((water.unwrap() - 18.015).abs() < 0.001);
+
+    assert!(molar_mass("heavy", 2, "15.999", 1).is_err());
+    assert!(molar_mass("1.008", 2, "", 1).is_err());
+}
@@ -0,0 +1,26 @@
+//! Lesson: `snippets/src/matching.rs` (`match_enum`)
+//!
+//! The test doesn't compile. Handle every variant of `Element` in `is_noble_gas` without using `_`.
+
+#[allow(dead_code)]
+enum Element {
+    Hydrogen,
+    Helium,
+    Neon,
+    Iron,
+}
+
+fn is_noble_gas(element: Element) -> bool {
+    match element {
+        Element::Helium => true,
+        Element::Iron => false,
+    }
+}
+
+#[test]
+fn match_enum() {
+    assert!(is_noble_gas(Element::Helium));
+    assert!(is_noble_gas(Element::Neon));
+    assert!(!is_noble_gas(Element::Hydrogen));
+    assert!(!is_noble_gas(Element::Iron));
+}
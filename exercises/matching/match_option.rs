@@ -0,0 +1,28 @@
+//! Lesson: `snippets/src/matching.rs` (`match_option`)
+//!
+//! `symbol` panics with `todo!()`. Implement it with a `match` on the result of `position`.
+
+const SYMBOLS: [&str; 4] = ["H", "He", "Li", "Be"];
+
+/// The symbol of the element with the atomic number, `None` if it is not in `SYMBOLS`.
+fn symbol(atomic_number: usize) -> Option<&'static str> {
+    todo!("return `Some(SYMBOLS[atomic_number - 1])` or `None`, don't forget `0`")
+}
+
+/// The atomic number of the symbol, `0` if it is not in `SYMBOLS`.
+fn atomic_number(symbol: &str) -> usize {
+    let position = SYMBOLS.iter().position(|known| *known == symbol); // <- `Option<usize>`
+
+    todo!("match on `position`: {position:?}")
+}
+
+#[test]
+fn match_option() {
+    assert_eq!(symbol(1), Some("H"));
+    assert_eq!(symbol(4), Some("Be"));
+    assert_eq!(symbol(0), None);
+    assert_eq!(symbol(5), None);
+
+    assert_eq!(atomic_number("He"), 2);
+    assert_eq!(atomic_number("Fe"), 0);
+}
@@ -0,0 +1,17 @@
+//! Lesson: `snippets/src/matching.rs` (`match_result`)
+//!
+//! `describe` panics with `todo!()`. Implement it with one `match` on the result, using a match guard (`if`) for the big numbers.
+
+/// - `Ok(n)` with `n` up to 118: `"element n"`
+/// - `Ok(n)` above 118: `"unknown element"`
+/// - `Err(message)`: `"error: message"`
+fn describe(atomic_number: Result<u32, String>) -> String {
+    todo!("{atomic_number:?}")
+}
+
+#[test]
+fn match_result() {
+    assert_eq!(describe(Ok(26)), "element 26");
+    assert_eq!(describe(Ok(119)), "unknown element");
+    assert_eq!(describe(Err("not a number".to_string())), "error: not a number");
+}
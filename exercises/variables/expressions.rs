@@ -0,0 +1,18 @@
+//! Lesson: `snippets/src/variables.rs` (`expression`)
+//!
+//! The test doesn't compile. Make it compile by removing (not adding) characters.
+
+fn neutrons(mass_number: u32, atomic_number: u32) -> u32 {
+    mass_number - atomic_number;
+}
+
+#[test]
+fn expressions() {
+    let protons = {
+        let atomic_number = 26;
+        atomic_number;
+    };
+
+    assert_eq!(protons, 26);
+    assert_eq!(neutrons(56, protons), 30);
+}
@@ -0,0 +1,12 @@
+//! Lesson: `snippets/src/variables.rs` (`mutability`)
+//!
+//! The test doesn't compile. Make it compile without changing the assertions.
+
+#[test]
+fn mutability() {
+    let electrons = 1;
+
+    electrons += 1; // <- hydrogen takes an electron
+
+    assert_eq!(electrons, 2);
+}
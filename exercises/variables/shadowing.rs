@@ -0,0 +1,20 @@
+//! Lesson: `snippets/src/variables.rs` (`shadowing`)
+//!
+//! The test compiles but the assertions fail. Replace the expected values (`0`) with the values the variables really have.
+
+#[test]
+fn shadowing() {
+    let weight = "55.845";
+    let weight: f32 = weight.parse().unwrap(); // <- a new variable with the same name (and a different type)
+
+    let symbol = "Fe";
+
+    {
+        let symbol = "Cu";
+
+        assert_eq!(symbol.len(), 0);
+    }
+
+    assert_eq!(weight.round(), 0.0);
+    assert_eq!(symbol, "0");
+}
@@ -0,0 +1,14 @@
+//! Examples show how to use the library and are compiled (but not run) by `cargo test`.
+//! Run it with `cargo run --example lookup`.
+
+use minimal_example::{elements::metals, find, Element};
+
+fn main() {
+    let gold: &Element = find("au").unwrap();
+
+    println!("{} has the atomic number {}", gold.name, gold.number);
+
+    for symbol in ["Ag", "Fe"] {
+        println!("{symbol} is noble: {}", metals::is_noble(symbol));
+    }
+}
@@ -0,0 +1,23 @@
+//! A second binary of the package: `cargo run --bin periodic_table -- fe au xyz`
+//! Without arguments it prints all metals.
+
+use minimal_example::elements::metals;
+
+fn main() {
+    let symbols: Vec<String> = std::env::args().skip(1).collect();
+
+    if symbols.is_empty() {
+        for metal in metals::metals() {
+            println!("{:>3} {:<2} {}", metal.number, metal.symbol, metal.name);
+        }
+
+        return;
+    }
+
+    for symbol in symbols {
+        match minimal_example::describe(&symbol) {
+            Some(description) => println!("{description}"),
+            None => println!("{symbol} is not in the periodic table"),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! `mod elements;` in lib.rs loads this file, its submodules are loaded from the directory `elements/`.
+
+pub mod metals;
+mod validation; // <- private: only `elements` and its children (`metals`) can see it
+
+use crate::Element;
+
+/// Looks up an element by its symbol, ignoring the case (`"fe"`, `"FE"` and `"Fe"` are iron).
+pub fn find(symbol: &str) -> Option<&'static Element> {
+    let symbol = validation::normalize(symbol)?;
+
+    crate::ELEMENTS
+        .iter()
+        .find(|element| element.symbol == symbol)
+}
+
+/// A sentence about the element, e.g. `Iron (Fe, 26) is a metal`.
+pub fn describe(symbol: &str) -> Option<String> {
+    let element = find(symbol)?;
+
+    let kind = if metals::is_metal(element) {
+        "a metal"
+    } else {
+        "not a metal"
+    };
+
+    Some(format!(
+        "{} ({}, {}) is {}",
+        element.name, element.symbol, element.number, kind
+    ))
+}
@@ -0,0 +1,23 @@
+//! A public submodule: reachable from other crates as `minimal_example::elements::metals`.
+
+use crate::Element;
+
+/// All metals, in order of their atomic number.
+pub fn metals() -> impl Iterator<Item = &'static Element> {
+    crate::ELEMENTS.iter().filter(|element| element.metal)
+}
+
+/// Whether the symbol belongs to a noble metal (one that doesn't corrode).
+pub fn is_noble(symbol: &str) -> bool {
+    // `validation` is private to `elements`, but `normalize` is `pub(in crate::elements)` so the sibling module `metals` can call it
+    let Some(symbol) = super::validation::normalize(symbol) else {
+        return false;
+    };
+
+    ["Ag", "Au"].contains(&symbol.as_str())
+}
+
+/// `pub(super)`: only the parent module `elements` can call this, other crates and `periodic_table` can't.
+pub(super) fn is_metal(element: &Element) -> bool {
+    element.metal
+}
@@ -0,0 +1,27 @@
+//! A private module: only `elements` and its children can use it.
+
+/// Turns user input into the spelling of a symbol (`"fE"` -> `"Fe"`), `None` if it can't be a symbol.
+/// `pub(in crate::elements)`: visible in `elements` and all its descendants, but not in `periodic_table` or lib.rs.
+pub(in crate::elements) fn normalize(symbol: &str) -> Option<String> {
+    let symbol = symbol.trim();
+
+    if symbol.is_empty() || symbol.len() > 2 || !symbol.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let (first, rest) = symbol.split_at(1);
+
+    Some(first.to_ascii_uppercase() + &rest.to_ascii_lowercase())
+}
+
+/// Unit tests are part of the crate, so they can test private items.
+/// Integration tests (`tests/visibility.rs`) only see the public API.
+#[test]
+fn normalize_symbols() {
+    assert_eq!(normalize("fe").as_deref(), Some("Fe"));
+    assert_eq!(normalize(" AU ").as_deref(), Some("Au"));
+    assert_eq!(normalize("c").as_deref(), Some("C"));
+    assert_eq!(normalize("Xyz"), None);
+    assert_eq!(normalize("F3"), None);
+    assert_eq!(normalize(""), None);
+}
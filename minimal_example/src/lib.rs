@@ -1,3 +1,61 @@
+//! A package contains one or more crates. A crate is the unit of compilation: the library (`src/lib.rs`),
+//! every binary (`src/main.rs`, `src/bin/*.rs`), every integration test (`tests/*.rs`), every example (`examples/*.rs`)
+//! and every doctest are compiled as separate crates. Only the library can be used by the others, and only its `pub` items.
+//!
+//! Inside of a crate the code is organised in a tree of modules. `mod name;` loads the module from `name.rs`
+//! (or `name/mod.rs`), its submodules live in the directory `name/`:
+//!
+//! ```text
+//! minimal_example           src/lib.rs
+//! ├── elements              src/elements.rs
+//! │   ├── metals            src/elements/metals.rs
+//! │   └── validation        src/elements/validation.rs   (private)
+//! └── periodic_table        src/periodic_table.rs        (private, re-exported)
+//! ```
+//!
+//! Everything is private by default: visible in the module where it is defined and in its children.
+//! Visibility modifiers open it up further:
+//!
+//! - `pub(super)`: the parent module (`elements::metals::is_metal`)
+//! - `pub(in path)`: the given ancestor module and all its children (`elements::validation::normalize`)
+//! - `pub(crate)`: the whole crate, but not the other crates of the package (`ELEMENTS`, `Element::metal`)
+//! - `pub`: everyone who can reach the item through public modules or re-exports
+//!
+//! `pub(crate)` items are invisible to the binaries, tests and examples, just like to any other crate:
+//!
+//! ```compile_fail,E0603
+//! use minimal_example::ELEMENTS; // <- static import `ELEMENTS` is private
+//! ```
+//!
+//! A private module hides all its items, even the `pub` ones, unless they are re-exported:
+//!
+//! ```compile_fail,E0603
+//! use minimal_example::elements::validation::normalize; // <- module `validation` is private
+//! ```
+//!
+//! ```compile_fail,E0616
+//! let iron = minimal_example::find("Fe").unwrap();
+//!
+//! assert!(iron.metal); // <- field `metal` of struct `Element` is private
+//! ```
+//!
+//! A struct with a private field can't be constructed outside of its crate, only through the functions it provides:
+//!
+//! ```compile_fail,E0451
+//! let element = minimal_example::Element { number: 0, symbol: "X", name: "Unknown", metal: false };
+//! ```
+
+pub mod elements;
+mod periodic_table;
+
+// `pub use` re-exports an item under a new path: users write `minimal_example::find` instead of `minimal_example::elements::find`
+// and can use `Element` although the module `periodic_table` is private.
+pub use elements::{describe, find};
+pub use periodic_table::Element;
+
+// `pub(crate) use` makes the item available in the whole crate as `crate::ELEMENTS` without making it public.
+pub(crate) use periodic_table::ELEMENTS;
+
 // Everything in Rust is by default private.
 // The `pub` keyword is needed to make the constant visible to the binary.
 // `pub(crate)` would make it visible to only the current crate. Which the binary is NOT part of.
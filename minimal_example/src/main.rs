@@ -3,9 +3,16 @@
 //! `main.rs` is the root of your crate's binary.
 //! The are specifically TWO independent crates and can have two different names -> Cargo.toml.
 //! Importing to the binary from the library functions the same as importing from any other crate.
+//!
+//! More binaries can be added in `src/bin/`, every file is its own crate: `cargo run --bin periodic_table`.
 
 use minimal_example::ANSWER;
 
 fn main() {
     println!("Answer? {}!", ANSWER);
+
+    // re-exported items are used like any other public item
+    println!("{}", minimal_example::describe("Fe").unwrap());
+
+    // let elements = minimal_example::ELEMENTS; // Error: static `ELEMENTS` is private -> `pub(crate)` doesn't include the binary
 }
@@ -0,0 +1,61 @@
+//! The module is private (`mod periodic_table;` in lib.rs), `Element` is public through the re-export `pub use periodic_table::Element`.
+
+/// A chemical element. The public fields can be read everywhere, `metal` only inside of this crate.
+#[derive(Debug, PartialEq)]
+pub struct Element {
+    pub number: u8,
+    pub symbol: &'static str,
+    pub name: &'static str,
+    pub(crate) metal: bool, // <- `Element { .. }` can't be written outside of the crate because of this field
+}
+
+pub(crate) static ELEMENTS: [Element; 8] = [
+    Element {
+        number: 1,
+        symbol: "H",
+        name: "Hydrogen",
+        metal: false,
+    },
+    Element {
+        number: 2,
+        symbol: "He",
+        name: "Helium",
+        metal: false,
+    },
+    Element {
+        number: 6,
+        symbol: "C",
+        name: "Carbon",
+        metal: false,
+    },
+    Element {
+        number: 8,
+        symbol: "O",
+        name: "Oxygen",
+        metal: false,
+    },
+    Element {
+        number: 26,
+        symbol: "Fe",
+        name: "Iron",
+        metal: true,
+    },
+    Element {
+        number: 29,
+        symbol: "Cu",
+        name: "Copper",
+        metal: true,
+    },
+    Element {
+        number: 47,
+        symbol: "Ag",
+        name: "Silver",
+        metal: true,
+    },
+    Element {
+        number: 79,
+        symbol: "Au",
+        name: "Gold",
+        metal: true,
+    },
+];
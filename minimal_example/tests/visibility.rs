@@ -0,0 +1,30 @@
+//! Integration tests are separate crates in `tests/`. They use the library like any other crate, only its public API is visible.
+
+use minimal_example::elements::metals;
+use minimal_example::{describe, find, Element, ANSWER};
+
+#[test]
+fn public_api() {
+    assert_eq!(ANSWER, 42);
+
+    let iron: &Element = find("FE").unwrap(); // <- `Element` is re-exported from the private module `periodic_table`
+
+    assert_eq!(iron.name, "Iron"); // <- public fields can be read
+    assert_eq!(iron.number, 26);
+
+    // assert!(iron.metal); // Error: field `metal` of struct `Element` is private -> `pub(crate)`
+
+    assert_eq!(describe("o").unwrap(), "Oxygen (O, 8) is not a metal");
+    assert_eq!(describe("Xyz"), None);
+}
+
+#[test]
+fn public_submodules() {
+    let metals: Vec<&str> = metals::metals().map(|metal| metal.symbol).collect();
+
+    assert_eq!(metals, ["Fe", "Cu", "Ag", "Au"]);
+    assert!(metals::is_noble("au"));
+    assert!(!metals::is_noble("fe"));
+
+    // metals::is_metal(find("Fe").unwrap()); // Error: function `is_metal` is private -> `pub(super)`
+}
@@ -0,0 +1,382 @@
+//! Benchmarks of equivalent workloads in Rust, C and Python.
+//!
+//! The README compares Rust against C and Python, these benchmarks measure the comparisons:
+//! - summing an array in pure Rust vs numpy vs pure Python (`--features python`, see numpy.rs)
+//! - calling `addc` through the FFI vs a native Rust function (`--features cc`, see ffi.rs)
+//! - incrementing an `Arc<Mutex<_>>` vs an atomic (see references.rs)
+//! - static dispatch with generics vs dynamic dispatch with trait objects (see dyn_dispatch.rs)
+//!
+//! `cargo bench --features cc,python` runs all of them (optionally filtered by name: `cargo bench -- counter`).
+//! The python benchmarks need numpy installed for the interpreter pyo3 links.
+//!
+//! Every benchmark is compared against a baseline in `target/bench_baseline.json` (or `$BENCH_BASELINE`),
+//! slowdowns above 25% are reported as regressions and make the run fail. The results and the comparison are written to
+//! `target/bench_report.json` (or `$BENCH_REPORT`), `target` is `$CARGO_TARGET_DIR` if it's set.
+//! The baseline is only replaced with `cargo bench --bench comparisons -- --save-baseline`, so a regression is reported by every run until it's fixed
+//! (or accepted by saving a new baseline). Benchmarks that are filtered out keep their result in the baseline.
+
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+const SAMPLES: usize = 20;
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+const REGRESSION_THRESHOLD: f64 = 0.25;
+
+struct Measurement {
+    name: String,
+    ns_per_iter: f64,
+}
+
+/// Runs `f` in batches of iterations sized so that one batch takes about `SAMPLE_TIME`
+/// and returns the median time per iteration over all batches.
+fn bench<R>(name: &str, mut f: impl FnMut() -> R) -> Measurement {
+    let mut iterations: u64 = 1;
+
+    // warm up and calibrate the number of iterations per sample
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            black_box(f());
+        }
+        if start.elapsed() >= SAMPLE_TIME || iterations >= 1 << 30 {
+            break;
+        }
+        iterations *= 2;
+    }
+
+    let mut samples: Vec<f64> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(f());
+            }
+            start.elapsed().as_nanos() as f64 / iterations as f64
+        })
+        .collect();
+
+    samples.sort_by(|a, b| a.total_cmp(b));
+
+    Measurement {
+        name: name.to_string(),
+        ns_per_iter: samples[SAMPLES / 2],
+    }
+}
+
+fn sum_array(filter: &Filter, results: &mut Vec<Measurement>) {
+    let values: Vec<f64> = (0..10_000).map(|x| x as f64).collect();
+
+    filter.run(results, "sum_array/rust", || {
+        black_box(&values).iter().sum::<f64>()
+    });
+
+    #[cfg(feature = "python")]
+    {
+        use numpy::IntoPyArray;
+        use pyo3::types::PyList;
+
+        pyo3::prepare_freethreaded_python();
+
+        pyo3::Python::with_gil(|py| {
+            let np = py.import("numpy").expect("numpy is not installed");
+            let np_sum = np.getattr("sum").unwrap();
+            let builtin_sum = py.import("builtins").unwrap().getattr("sum").unwrap();
+
+            let array = values.clone().into_pyarray(py);
+            let list = PyList::new(py, &values);
+
+            filter.run(results, "sum_array/numpy", || {
+                np_sum.call1((array,)).unwrap().extract::<f64>().unwrap()
+            });
+
+            // the conversion from `Vec` to a numpy array is part of the cost of calling numpy from Rust
+            filter.run(results, "sum_array/numpy_with_conversion", || {
+                let array = values.clone().into_pyarray(py);
+                np_sum.call1((array,)).unwrap().extract::<f64>().unwrap()
+            });
+
+            filter.run(results, "sum_array/python", || {
+                builtin_sum
+                    .call1((list,))
+                    .unwrap()
+                    .extract::<f64>()
+                    .unwrap()
+            });
+        });
+    }
+}
+
+fn add(filter: &Filter, results: &mut Vec<Measurement>) {
+    #[inline(never)] // the same as the C function, which can't be inlined across the FFI boundary
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    filter.run(results, "add/rust", || add(black_box(1), black_box(2)));
+
+    #[cfg(feature = "cc")]
+    {
+        // the static library is built by build.rs, the bench has to link it explicitly because it does not link the library crate
+        #[link(name = "ffi", kind = "static")]
+        extern "C" {
+            fn addc(a: i32, b: i32) -> i32;
+        }
+
+        filter.run(results, "add/c_ffi", || unsafe {
+            addc(black_box(1), black_box(2))
+        });
+    }
+}
+
+fn counter(filter: &Filter, results: &mut Vec<Measurement>) {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    const INCREMENTS: u64 = 1_000;
+    const THREADS: usize = 4;
+
+    let mutex = Arc::new(Mutex::new(0u64));
+    let atomic = Arc::new(AtomicU64::new(0));
+
+    filter.run(results, "counter/arc_mutex", || {
+        for _ in 0..INCREMENTS {
+            *mutex.lock().unwrap() += 1;
+        }
+    });
+
+    filter.run(results, "counter/atomic", || {
+        for _ in 0..INCREMENTS {
+            atomic.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    // with several threads incrementing at the same time the mutex is contended
+    filter.run(results, "counter/arc_mutex_contended", || {
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for _ in 0..INCREMENTS {
+                        *mutex.lock().unwrap() += 1;
+                    }
+                });
+            }
+        })
+    });
+
+    filter.run(results, "counter/atomic_contended", || {
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for _ in 0..INCREMENTS {
+                        atomic.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        })
+    });
+}
+
+fn dispatch(filter: &Filter, results: &mut Vec<Measurement>) {
+    trait Shape {
+        fn area(&self) -> f64;
+    }
+
+    struct Square(f64);
+
+    impl Shape for Square {
+        fn area(&self) -> f64 {
+            self.0 * self.0
+        }
+    }
+
+    fn total_generic<T: Shape>(shapes: &[T]) -> f64 {
+        shapes.iter().map(|shape| shape.area()).sum()
+    }
+
+    fn total_dyn(shapes: &[Box<dyn Shape>]) -> f64 {
+        shapes.iter().map(|shape| shape.area()).sum()
+    }
+
+    let squares: Vec<Square> = (0..1_000).map(|x| Square(x as f64)).collect();
+    let shapes: Vec<Box<dyn Shape>> = (0..1_000)
+        .map(|x| Box::new(Square(x as f64)) as Box<dyn Shape>)
+        .collect();
+
+    filter.run(results, "dispatch/generic", || {
+        total_generic(black_box(&squares))
+    });
+    filter.run(results, "dispatch/dyn", || total_dyn(black_box(&shapes)));
+}
+
+/// Only runs the benchmarks whose name contains one of the filters given on the command line.
+struct Filter(Vec<String>);
+
+impl Filter {
+    fn run<R>(&self, results: &mut Vec<Measurement>, name: &str, f: impl FnMut() -> R) {
+        if self.0.is_empty() || self.0.iter().any(|filter| name.contains(filter.as_str())) {
+            let measurement = bench(name, f);
+            println!(
+                "{:<40} {:>14.1} ns/iter",
+                measurement.name, measurement.ns_per_iter
+            );
+            results.push(measurement);
+        }
+    }
+}
+
+/// Reads the benchmarks of a previous report, one benchmark per line as written by `write_report`.
+fn read_report(report: &str) -> Vec<(String, f64)> {
+    report
+        .lines()
+        .filter_map(|line| {
+            let name = line.split("\"name\": \"").nth(1)?.split('"').next()?;
+            let ns_per_iter = line
+                .split("\"ns_per_iter\": ")
+                .nth(1)?
+                .split([',', '}'])
+                .next()?
+                .trim()
+                .parse()
+                .ok()?;
+
+            Some((name.to_string(), ns_per_iter))
+        })
+        .collect()
+}
+
+/// The results and, for every benchmark in the baseline, the change against it. Returns the report and the number of regressions.
+fn write_report(results: &[Measurement], baseline: &[(String, f64)]) -> (String, usize) {
+    let mut regressions = 0;
+
+    let lines: Vec<String> = results
+        .iter()
+        .map(|measurement| {
+            let mut line = format!(
+                "    {{\"name\": \"{}\", \"ns_per_iter\": {:.3}",
+                measurement.name, measurement.ns_per_iter
+            );
+
+            if let Some((_, before)) = baseline.iter().find(|(name, _)| *name == measurement.name) {
+                let change = measurement.ns_per_iter / before - 1.0;
+                let regression = change > REGRESSION_THRESHOLD;
+
+                if regression {
+                    regressions += 1;
+                    println!(
+                        "regression: {} {:.1} ns/iter -> {:.1} ns/iter ({:+.0}%)",
+                        measurement.name,
+                        before,
+                        measurement.ns_per_iter,
+                        change * 100.0
+                    );
+                }
+
+                line += &format!(
+                    ", \"baseline_ns_per_iter\": {before:.3}, \"change\": {change:.4}, \"regression\": {regression}"
+                );
+            }
+
+            line + "}"
+        })
+        .collect();
+
+    (format_benchmarks(&lines), regressions)
+}
+
+/// The results as the new baseline, benchmarks that were filtered out keep their result from the old one.
+fn write_baseline(results: &[Measurement], baseline: &[(String, f64)]) -> String {
+    let kept = baseline
+        .iter()
+        .filter(|(name, _)| !results.iter().any(|measurement| measurement.name == *name))
+        .map(|(name, ns_per_iter)| (name.as_str(), *ns_per_iter));
+
+    let lines: Vec<String> = results
+        .iter()
+        .map(|measurement| (measurement.name.as_str(), measurement.ns_per_iter))
+        .chain(kept)
+        .map(|(name, ns_per_iter)| {
+            format!("    {{\"name\": \"{name}\", \"ns_per_iter\": {ns_per_iter:.3}}}")
+        })
+        .collect();
+
+    format_benchmarks(&lines)
+}
+
+fn format_benchmarks(lines: &[String]) -> String {
+    format!("{{\n  \"benchmarks\": [\n{}\n  ]\n}}\n", lines.join(",\n"))
+}
+
+/// `$variable`, or the file in the target directory (`$CARGO_TARGET_DIR` or the `target` of the workspace).
+fn output_path(variable: &str, file: &str) -> PathBuf {
+    if let Some(path) = std::env::var_os(variable) {
+        return PathBuf::from(path);
+    }
+
+    let target = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("target"));
+
+    target.join(file)
+}
+
+fn write(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).expect("Failed to create the report directory");
+    }
+    std::fs::write(path, contents)
+        .unwrap_or_else(|error| panic!("Failed to write {}: {error}", path.display()));
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let save_baseline = args.iter().any(|arg| arg == "--save-baseline");
+
+    // `cargo bench` passes `--bench`, everything else that isn't an option is a name filter
+    let filter = Filter(
+        args.into_iter()
+            .filter(|arg| !arg.starts_with("--"))
+            .collect(),
+    );
+
+    let mut results = Vec::new();
+
+    sum_array(&filter, &mut results);
+    add(&filter, &mut results);
+    counter(&filter, &mut results);
+    dispatch(&filter, &mut results);
+
+    let baseline_path = output_path("BENCH_BASELINE", "bench_baseline.json");
+    let report_path = output_path("BENCH_REPORT", "bench_report.json");
+
+    let baseline = std::fs::read_to_string(&baseline_path)
+        .map(|baseline| read_report(&baseline))
+        .unwrap_or_default();
+
+    let (report, regressions) = write_report(&results, &baseline);
+
+    write(&report_path, &report);
+
+    println!(
+        "report written to {} ({regressions} regressions)",
+        report_path.display()
+    );
+
+    if save_baseline {
+        write(&baseline_path, &write_baseline(&results, &baseline));
+
+        println!("baseline written to {}", baseline_path.display());
+    } else if baseline.is_empty() {
+        println!(
+            "no baseline in {}, save one with `cargo bench --bench comparisons -- --save-baseline`",
+            baseline_path.display()
+        );
+    }
+
+    if regressions > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
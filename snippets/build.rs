@@ -1,4 +1,31 @@
 fn main() {
+    // cargo passes the enabled features as `CARGO_FEATURE_<NAME>` variables, the `features` lesson compares them with `cfg!(feature = ..)`
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(name, _)| {
+            let feature = name.strip_prefix("CARGO_FEATURE_")?;
+            Some(feature.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+
+    println!(
+        "cargo:rustc-env=BUILD_SCRIPT_FEATURES={}",
+        features.join(",")
+    );
+
+    // relative paths would depend on the directory cargo is invoked from (package or workspace root)
     #[cfg(feature = "cc")]
-    cc::Build::new().file("../c/src/ffi.c").compile("ffi");
+    {
+        let ffi = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../c/src/ffi.c");
+
+        println!("cargo:rerun-if-changed={}", ffi.display());
+
+        cc::Build::new().file(ffi).compile("ffi");
+
+        // the C harness of the tests compiles for the same target
+        println!(
+            "cargo:rustc-env=TARGET={}",
+            std::env::var("TARGET").unwrap()
+        );
+    }
 }
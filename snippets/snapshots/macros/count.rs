@@ -0,0 +1 @@
+fn expansion() { 1usize + (1usize + (1usize + 0usize)); }
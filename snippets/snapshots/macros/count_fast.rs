@@ -0,0 +1 @@
+fn expansion() { <[()]>::len(&[(), (), ()]); }
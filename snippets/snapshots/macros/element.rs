@@ -0,0 +1 @@
+fn expansion() { let _ = crate::macros::Element { symbol: "Fe" }; }
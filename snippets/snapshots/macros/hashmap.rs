@@ -0,0 +1,11 @@
+fn expansion() {
+    let _ =
+        {
+            let mut map =
+                ::std::collections::HashMap::with_capacity(<[()]>::len(&[(),
+                                    ()]));
+            map.insert("Fe", 55.845);
+            map.insert("Cu", 63.546);
+            map
+        };
+}
@@ -0,0 +1,4 @@
+fn expansion() {
+    let vec = 1;
+    { let mut vec: Vec<i32> = Vec::new(); vec.push(vec); vec.push(vec); vec };
+}
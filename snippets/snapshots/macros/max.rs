@@ -0,0 +1,7 @@
+fn expansion() {
+    {
+        let a = 3;
+        let b = { let a = 1; let b = 2; if a > b { a } else { b } };
+        if a > b { a } else { b }
+    };
+}
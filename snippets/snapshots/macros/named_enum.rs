@@ -0,0 +1,9 @@
+fn expansion() {
+    enum Metal { Iron, Copper, }
+    impl Metal {
+        pub const ALL: [Metal; 1 + (1 + 0)] = [Metal::Iron, Metal::Copper];
+        pub fn name(&self) -> &'static str {
+            match self { Metal::Iron => "Iron", Metal::Copper => "Copper", }
+        }
+    }
+}
@@ -0,0 +1 @@
+fn expansion() { ((2 + 3) * 4); }
@@ -0,0 +1,13 @@
+fn expansion() {
+    enum Door { Open, Closed, }
+    enum Action { Push, Pull, }
+    impl Door {
+        fn next(self, event: Action) -> Option<Door> {
+            match (self, event) {
+                (Door::Closed, Action::Push) => Some(Door::Open),
+                (Door::Open, Action::Pull) => Some(Door::Closed),
+                _ => None,
+            }
+        }
+    }
+}
@@ -1,6 +1,31 @@
 //! Rust provides no implicit type conversion (coercion) between primitive types.
 //! But, explicit type conversion (casting) can be performed using the as keyword `as` or `from`.
 
+/// Converts a reading (`i32`) into a percentage (`u8`), `None` if the reading is not between 0 and 100.
+/// `as` would silently wrap values that don't fit into a `u8`, `try_from` returns an error instead.
+///
+/// ```
+/// use rust_snippets::casting::to_percent;
+///
+/// assert_eq!(to_percent(42), Some(42));
+/// assert_eq!(to_percent(101), None);
+/// assert_eq!(to_percent(300), None); // `300 as u8` would be 44
+/// assert_eq!(to_percent(-1), None); // `-1 as u8` would be 255
+///
+/// let reading = 300;
+///
+/// assert_eq!(reading as u8, 44); // the lowest 8 bits of 300
+/// ```
+///
+/// Unwrapping a failed conversion panics:
+///
+/// ```should_panic
+/// let percent = u8::try_from(300).unwrap(); // panics: out of range integral type conversion attempted
+/// ```
+pub fn to_percent(value: i32) -> Option<u8> {
+    u8::try_from(value).ok().filter(|percent| *percent <= 100)
+}
+
 /// When casting between primitive types with different size, extra care must be taken to avoid overflow.
 /// This can be done by using the `try_from` and `try_into` methods.
 #[test]
@@ -30,3 +55,29 @@ fn casting() {
 
     assert_eq!(b, u32::MAX);
 }
+
+/// `try_from` is lossless, `as` only keeps the value for values that are in range.
+#[test]
+fn try_from_round_trip() {
+    use crate::property_testing::check;
+    use proptest::prelude::*;
+
+    // property: a successful conversion converts back to the original value, a failed one was out of range
+    check(&any::<i64>(), |a| {
+        match i32::try_from(a) {
+            Ok(b) => prop_assert_eq!(i64::from(b), a),
+            Err(_) => prop_assert!(a < i32::MIN as i64 || a > i32::MAX as i64),
+        }
+
+        Ok(())
+    });
+
+    // property: `as` between integers of the same size round-trips, but changes the value of negative numbers
+    check(&any::<i32>(), |a| {
+        prop_assert_eq!(u32::try_from(a).is_ok(), a >= 0);
+        prop_assert_eq!(a as u32 as i32, a); // `as` between same sized integers reinterprets the bits -> round-trips
+        prop_assert_eq!(a as u32 == u32::try_from(a).unwrap_or(0), a >= 0); // but the value only stays the same for positive numbers
+
+        Ok(())
+    });
+}
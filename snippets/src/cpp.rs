@@ -0,0 +1,298 @@
+//! https://doc.rust-lang.org/book/ch15-00-smart-pointers.html
+//!
+//! C++ has the same ownership ideas as Rust: `std::unique_ptr` is a `Box`, `std::shared_ptr` an `Rc` (or `Arc`) and destructors run like `Drop` (RAII).
+//! The difference is that C++ doesn't check them: a moved value can still be used, and iterators and views can outlive the memory they point to.
+//! The tests compile the C++ versions (`cpp/src`) with the host's C++ compiler and run them (`--features cc`).
+//!
+//! Rust doesn't compile the use of a moved value:
+//!
+//! ```compile_fail,E0382
+//! let number = Box::new(1);
+//! let owner = number; // <- value moved here
+//!
+//! println!("{}", *number); // <- value borrowed here after move
+//! ```
+//!
+//! or changing a vector while iterating over it:
+//!
+//! ```compile_fail,E0502
+//! let mut numbers = vec![1, 2, 3];
+//!
+//! for number in &numbers { // <- immutable borrow occurs here
+//!     if *number == 2 {
+//!         numbers.push(4); // <- mutable borrow occurs here
+//!     }
+//! }
+//! ```
+//!
+//! or a view (`&str`) of a string that was already dropped:
+//!
+//! ```compile_fail,E0716
+//! fn describe(element: &str) -> String {
+//!     format!("{element} is the first element of the periodic table")
+//! }
+//!
+//! let view: &str = describe("hydrogen").as_str(); // <- temporary value is freed at the end of this statement
+//!
+//! println!("{view}"); // <- borrow later used here
+//! ```
+
+#[cfg(all(test, feature = "cc"))]
+use crate::harness::c::Program;
+
+/// `Box` owns its value alone like `std::unique_ptr`, `Rc` counts its owners like `std::shared_ptr`.
+/// Both destroy the value when the last owner goes out of scope, in the reverse order of declaration.
+/// The C++ and the Rust version print the same.
+#[cfg(feature = "cc")]
+#[test]
+fn unique_and_shared_ownership() {
+    /* ownership.cpp
+
+    #include <iostream>
+    #include <memory>
+
+    struct Atom {
+        const char* name;
+
+        explicit Atom(const char* name) : name(name) {}
+
+        ~Atom() {
+            std::cout << "destroy " << name << std::endl;
+        }
+    };
+
+    int main() {
+        std::unique_ptr<Atom> hydrogen = std::make_unique<Atom>("H");
+        std::unique_ptr<Atom> owner = std::move(hydrogen); // unique_ptr can only be moved, not copied
+
+        std::shared_ptr<Atom> oxygen = std::make_shared<Atom>("O");
+
+        {
+            std::shared_ptr<Atom> shared = oxygen; // a copy increments the reference count
+
+            std::cout << "owners of O: " << oxygen.use_count() << std::endl;
+        }
+
+        std::cout << "owners of O: " << oxygen.use_count() << std::endl;
+
+        return 0; // destroyed in reverse order: oxygen, then owner
+    }
+
+    */
+
+    use std::cell::RefCell;
+    use std::fmt::Write;
+    use std::rc::Rc;
+
+    let cpp = Program::file("ownership.cpp")
+        .documented_in(include_str!("cpp.rs"))
+        .compile()
+        .unwrap()
+        .run();
+
+    let stdout = RefCell::new(String::new()); // <- instead of printing, so the output can be compared
+
+    struct Atom<'a> {
+        name: &'static str,
+        stdout: &'a RefCell<String>,
+    }
+
+    impl Drop for Atom<'_> {
+        // <- the destructor
+        fn drop(&mut self) {
+            writeln!(self.stdout.borrow_mut(), "destroy {}", self.name).unwrap();
+        }
+    }
+
+    {
+        let hydrogen = Box::new(Atom {
+            name: "H",
+            stdout: &stdout,
+        });
+        let _owner = hydrogen; // <- a move, `hydrogen` can't be used anymore (not even to check if it's empty)
+
+        let oxygen = Rc::new(Atom {
+            name: "O",
+            stdout: &stdout,
+        });
+
+        {
+            let _shared = Rc::clone(&oxygen); // <- increments the reference count
+
+            writeln!(
+                stdout.borrow_mut(),
+                "owners of O: {}",
+                Rc::strong_count(&oxygen)
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            stdout.borrow_mut(),
+            "owners of O: {}",
+            Rc::strong_count(&oxygen)
+        )
+        .unwrap();
+    } // <- dropped in reverse order: oxygen, then _owner
+
+    cpp.assert_stdout(&stdout.into_inner());
+}
+
+/// `std::move` only casts to an rvalue reference, the moved-from object still exists and can be used.
+/// A moved `std::string` is usually empty, a moved `std::unique_ptr` is null and dereferencing it crashes.
+/// In Rust a move ends the life of the variable (E0382 above), `std::mem::take` and `Option::take` are the explicit versions that leave something behind.
+#[cfg(feature = "cc")]
+#[test]
+fn use_after_move() {
+    /* use_after_move.cpp
+
+    #include <iostream>
+    #include <memory>
+    #include <string>
+
+    int main() {
+        std::string name = "hydrogen";
+        std::string moved = std::move(name); // name is still usable, in a "valid but unspecified" state
+
+        std::cout << "name: '" << name << "', moved: '" << moved << "'" << std::endl;
+
+        std::unique_ptr<int> number = std::make_unique<int>(1);
+        std::unique_ptr<int> owner = std::move(number); // number is now a null pointer
+
+        std::cout << *number << std::endl; // dereferencing a null pointer
+
+        return 0;
+    }
+
+    */
+
+    let cpp = Program::file("use_after_move.cpp")
+        .documented_in(include_str!("cpp.rs"))
+        .compile()
+        .unwrap()
+        .run();
+
+    assert!(
+        cpp.stdout.starts_with("name: '', moved: 'hydrogen'\n"),
+        "{cpp}"
+    );
+    assert_ne!(cpp.code, Some(0), "{cpp}"); // <- killed by SIGSEGV on unix
+
+    let mut name = String::from("hydrogen");
+    let moved = std::mem::take(&mut name); // <- leaves `String::default()` behind
+
+    assert_eq!((name.as_str(), moved.as_str()), ("", "hydrogen"));
+
+    let mut number = Some(Box::new(1));
+    let owner = number.take(); // <- leaves `None` behind, the "null pointer" has to be handled to get the value
+
+    assert_eq!(number, None);
+    assert_eq!(owner.as_deref(), Some(&1));
+}
+
+/// `push_back` on a full `std::vector` moves the elements into a bigger allocation and frees the old one,
+/// iterators (and references like `int& last = numbers[2]`) still point into the old one.
+/// The C++ version compiles and reads freed memory (AddressSanitizer reports it), the Rust version doesn't compile (E0502 above, like the `v.pop()` example in [`references`](crate::references)).
+#[cfg(feature = "cc")]
+#[test]
+fn iterator_invalidation() {
+    /* iterator_invalidation.cpp
+
+    #include <iostream>
+    #include <vector>
+
+    int main() {
+        std::vector<int> numbers = {1, 2, 3};
+
+        for (auto it = numbers.begin(); it != numbers.end(); ++it) {
+            if (*it == 2) {
+                numbers.push_back(4); // the vector is full and moves its elements, it points into the freed memory
+            }
+
+            std::cout << *it << std::endl;
+        }
+
+        return 0;
+    }
+
+    */
+
+    let cpp = Program::file("iterator_invalidation.cpp")
+        .documented_in(include_str!("cpp.rs"))
+        .address_sanitizer()
+        .compile()
+        .unwrap()
+        .run();
+
+    assert!(cpp.stderr.contains("heap-use-after-free"), "{cpp}");
+    assert_ne!(cpp.code, Some(0));
+
+    // in Rust the changes are collected while iterating and applied afterwards
+    let mut numbers = vec![1, 2, 3];
+    let additions: Vec<i32> = numbers
+        .iter()
+        .filter(|&&number| number == 2)
+        .map(|_| 4)
+        .collect();
+
+    numbers.extend(additions);
+
+    assert_eq!(numbers, [1, 2, 3, 4]);
+
+    // or with indices, which stay valid when the vector reallocates
+    let mut numbers = vec![1, 2, 3];
+
+    for index in 0..numbers.len() {
+        if numbers[index] == 2 {
+            numbers.push(4);
+        }
+    }
+
+    assert_eq!(numbers, [1, 2, 3, 4]);
+}
+
+/// `std::string_view` is a pointer and a length like `&str`, but without a lifetime:
+/// a view of a temporary `std::string` compiles and dangles after the statement.
+/// Rust rejects the same code (E0716 above), the string has to be stored in a variable that lives as long as the view.
+#[cfg(feature = "cc")]
+#[test]
+fn dangling_string_view() {
+    /* string_view.cpp
+
+    #include <iostream>
+    #include <string>
+    #include <string_view>
+
+    std::string describe(const char* element) {
+        return std::string(element) + " is the first element of the periodic table";
+    }
+
+    int main() {
+        std::string_view view = describe("hydrogen"); // the string is destroyed at the end of the statement
+
+        std::cout << view << std::endl; // reading the freed memory of the string
+
+        return 0;
+    }
+
+    */
+
+    let cpp = Program::file("string_view.cpp")
+        .documented_in(include_str!("cpp.rs"))
+        .address_sanitizer()
+        .compile()
+        .unwrap()
+        .run();
+
+    assert!(cpp.stderr.contains("heap-use-after-free"), "{cpp}");
+    assert_ne!(cpp.code, Some(0));
+
+    fn describe(element: &str) -> String {
+        format!("{element} is the first element of the periodic table")
+    }
+
+    let description = describe("hydrogen"); // <- the owner
+    let view: &str = description.as_str(); // <- borrows from `description`, can't outlive it
+
+    assert_eq!(view, "hydrogen is the first element of the periodic table");
+}
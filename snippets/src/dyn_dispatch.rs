@@ -0,0 +1,439 @@
+//! https://doc.rust-lang.org/book/ch17-02-trait-objects.html
+//!
+//! A trait object `dyn Trait` is a value of some type that implements `Trait`, where the concrete type is only known at runtime.
+//! Trait objects are always behind a pointer (`&dyn Trait`, `Box<dyn Trait>`, `Rc<dyn Trait>`) because their size is not known at compile time.
+//! The pointer is a "fat pointer": a pointer to the data and a pointer to the vtable of the concrete type.
+//! The vtable is a table of function pointers (and the size, alignment and destructor of the type) generated by the compiler for every `impl Trait for Type`.
+//! Calling a method on a trait object looks up the function in the vtable at runtime -> dynamic dispatch.
+//!
+//! Not every trait can be made into a trait object -> Object Safety (dyn compatibility)
+//! https://doc.rust-lang.org/reference/items/traits.html#object-safety
+//!
+//! A trait is object safe if the compiler can build a vtable for it, which means all methods have to be callable without knowing the concrete type.
+//!
+//! `Clone` is not object safe because `clone` returns `Self`, whose size is unknown for a trait object:
+//!
+//! ```compile_fail,E0038
+//! let cloneable: Box<dyn Clone> = Box::new(1);
+//! ```
+//!
+//! Generic methods are not object safe because the vtable would need an entry for every possible `T`:
+//!
+//! ```compile_fail,E0038
+//! trait Shape {
+//!     fn scale<T: Into<f64>>(&self, factor: T) -> f64;
+//! }
+//!
+//! fn total(shapes: &[Box<dyn Shape>]) {}
+//! ```
+//!
+//! Associated functions without a receiver (`self`) can't be called on a trait object:
+//!
+//! ```compile_fail,E0038
+//! trait Shape {
+//!     fn new() -> Self;
+//! }
+//!
+//! fn total(shapes: &[Box<dyn Shape>]) {}
+//! ```
+//!
+//! Adding `where Self: Sized` to such a method excludes it from the vtable and makes the trait object safe again (see `object_safety`).
+
+/// A shape with an area. The trait is object safe, so different shapes can be stored together as `Box<dyn Shape>`.
+///
+/// ```
+/// use rust_snippets::dyn_dispatch::{total_area, Circle, Shape, Square};
+///
+/// let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square { side: 2.0 }), Box::new(Circle { radius: 1.0 })];
+///
+/// assert_eq!(total_area(&shapes), 4.0 + std::f64::consts::PI);
+/// ```
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Circle {
+    pub radius: f64,
+}
+
+pub struct Square {
+    pub side: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+/// The sum of the areas. `area` is looked up in the vtable of every shape at runtime.
+pub fn total_area(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
+/// The compiler generates a vtable for every `impl Trait for Type` and a trait object carries a pointer to it.
+/// This is a handwritten version of what the compiler generates for `dyn Metallic`.
+#[test]
+fn vtables() {
+    use std::mem::size_of;
+
+    trait Metallic {
+        fn name(&self) -> String;
+    }
+
+    struct Iron;
+
+    impl Metallic for Iron {
+        fn name(&self) -> String {
+            "Iron".to_string()
+        }
+    }
+
+    struct Gold {
+        carat: u8,
+    }
+
+    impl Metallic for Gold {
+        fn name(&self) -> String {
+            format!("{} carat Gold", self.carat)
+        }
+    }
+
+    // A reference to a concrete type is a thin pointer, a reference to a trait object is a fat pointer (data + vtable)
+    assert_eq!(size_of::<&Iron>(), size_of::<usize>());
+    assert_eq!(size_of::<&dyn Metallic>(), 2 * size_of::<usize>());
+    assert_eq!(size_of::<Box<dyn Metallic>>(), 2 * size_of::<usize>());
+
+    // Handwritten vtable -> one entry per method (plus size, alignment and `drop` in the real one)
+    struct MetallicVTable {
+        name: fn(*const ()) -> String,
+    }
+
+    // Handwritten trait object -> the type of the data is erased to `()`
+    struct DynMetallic {
+        data: *const (),
+        vtable: &'static MetallicVTable,
+    }
+
+    impl DynMetallic {
+        fn name(&self) -> String {
+            (self.vtable.name)(self.data) // look up the function in the vtable and call it with the data pointer
+        }
+    }
+
+    // One vtable per `impl`, each function casts the data pointer back to its concrete type
+    static IRON_VTABLE: MetallicVTable = MetallicVTable {
+        // SAFETY: the data pointer of a `DynMetallic` with this vtable always points to an `Iron`
+        name: |data| unsafe { &*(data as *const Iron) }.name(),
+    };
+
+    static GOLD_VTABLE: MetallicVTable = MetallicVTable {
+        // SAFETY: the data pointer of a `DynMetallic` with this vtable always points to a `Gold`
+        name: |data| unsafe { &*(data as *const Gold) }.name(),
+    };
+
+    let iron = Iron;
+    let gold = Gold { carat: 24 };
+
+    let handwritten = [
+        DynMetallic {
+            data: &iron as *const Iron as *const (),
+            vtable: &IRON_VTABLE,
+        },
+        DynMetallic {
+            data: &gold as *const Gold as *const (),
+            vtable: &GOLD_VTABLE,
+        },
+    ];
+
+    let compiler_generated: [&dyn Metallic; 2] = [&iron, &gold];
+
+    for (handwritten, generated) in handwritten.iter().zip(compiler_generated) {
+        assert_eq!(handwritten.name(), generated.name());
+    }
+}
+
+/// Methods that would make a trait not object safe can be excluded from the vtable with `where Self: Sized`.
+/// They can still be called on concrete types but not on trait objects.
+/// `Clone` is not object safe, the usual workaround is a `clone_box` method returning `Box<dyn Trait>`.
+#[test]
+fn object_safety() {
+    trait Shape {
+        fn area(&self) -> f64;
+
+        fn clone_box(&self) -> Box<dyn Shape>; // object safe replacement for `Clone`
+
+        // no receiver -> excluded from the vtable
+        fn new_unit() -> Self
+        where
+            Self: Sized;
+
+        // generic -> excluded from the vtable
+        fn scale<T: Into<f64>>(&self, factor: T) -> f64
+        where
+            Self: Sized,
+        {
+            self.area() * factor.into()
+        }
+    }
+
+    #[derive(Clone)]
+    struct Square(f64);
+
+    impl Shape for Square {
+        fn area(&self) -> f64 {
+            self.0 * self.0
+        }
+
+        fn clone_box(&self) -> Box<dyn Shape> {
+            Box::new(self.clone())
+        }
+
+        fn new_unit() -> Self {
+            Square(1.0)
+        }
+    }
+
+    let square = Square::new_unit();
+
+    assert_eq!(square.scale(2u8), 2.0); // callable on the concrete type
+
+    let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square(2.0)), Box::new(square)];
+
+    // shapes[0].scale(2u8); // Error: the `scale` method cannot be invoked on a trait object
+
+    let cloned: Vec<Box<dyn Shape>> = shapes.iter().map(|shape| shape.clone_box()).collect();
+
+    assert_eq!(cloned[0].area(), 4.0);
+    assert_eq!(cloned[1].area(), 1.0);
+}
+
+/// A trait object erases the concrete type, but it can be recovered with the `Any` trait.
+/// `Any` is implemented for every `'static` type and gives access to its `TypeId`.
+/// `downcast_ref::<T>()` returns `Some(&T)` if the concrete type is `T` and `None` otherwise.
+#[allow(clippy::type_id_on_box)] // clippy warns about the pitfall below
+#[test]
+fn any_downcasting() {
+    use std::any::{Any, TypeId};
+
+    trait Metallic: Any {
+        // `Any` as supertrait -> every `Metallic` is also `Any`
+        fn as_any(&self) -> &dyn Any;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Iron;
+
+    impl Metallic for Iron {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Gold {
+        carat: u8,
+    }
+
+    impl Metallic for Gold {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    let list_of_metals: Vec<Box<dyn Metallic>> = vec![Box::new(Iron), Box::new(Gold { carat: 18 })];
+
+    // `type_id` is a method of `Any` -> dispatched through the vtable to the concrete type
+    assert_eq!(list_of_metals[0].as_any().type_id(), TypeId::of::<Iron>());
+    assert_eq!(list_of_metals[1].as_any().type_id(), TypeId::of::<Gold>());
+
+    // Pitfall: `Box<dyn Metallic>` is itself `'static` and therefore `Any` -> the `TypeId` of the Box
+    assert_eq!(
+        list_of_metals[0].type_id(),
+        TypeId::of::<Box<dyn Metallic>>()
+    );
+
+    // Recover the concrete types
+    assert_eq!(
+        list_of_metals[0].as_any().downcast_ref::<Iron>(),
+        Some(&Iron)
+    );
+    assert_eq!(list_of_metals[0].as_any().downcast_ref::<Gold>(), None);
+    assert_eq!(
+        list_of_metals[1].as_any().downcast_ref::<Gold>(),
+        Some(&Gold { carat: 18 })
+    );
+
+    // `Box<dyn Any>` can be downcast by value -> `Err` gives the Box back
+    let boxed: Box<dyn Any> = Box::new(Gold { carat: 24 });
+
+    let boxed = boxed.downcast::<Iron>().unwrap_err();
+    let gold: Box<Gold> = boxed.downcast::<Gold>().unwrap();
+
+    assert_eq!(gold.carat, 24);
+}
+
+/// There are three ways to write a function that accepts any type implementing a trait:
+/// - generics `fn f<T: Shape>(x: &T)` -> static dispatch, the compiler generates a copy of `f` for every `T` (monomorphization)
+/// - `impl Trait` in argument position `fn f(x: &impl Shape)` -> syntactic sugar for the generic version
+/// - trait objects `fn f(x: &dyn Shape)` -> dynamic dispatch, one copy of `f` that calls through the vtable
+///
+/// Static dispatch can be inlined and optimized for every type but increases the binary size.
+/// Dynamic dispatch costs one indirect call per method call but allows mixing different types in one collection.
+/// The benchmarks in `benches/comparisons.rs` (`cargo bench -- dispatch`) measure the difference.
+#[test]
+fn dyn_vs_impl_vs_generics() {
+    trait Shape {
+        fn area(&self) -> f64;
+    }
+
+    struct Square(f64);
+
+    impl Shape for Square {
+        fn area(&self) -> f64 {
+            self.0 * self.0
+        }
+    }
+
+    struct Circle(f64);
+
+    impl Shape for Circle {
+        fn area(&self) -> f64 {
+            std::f64::consts::PI * self.0 * self.0
+        }
+    }
+
+    fn total_generic<T: Shape>(shapes: &[T]) -> f64 {
+        shapes.iter().map(|shape| shape.area()).sum()
+    }
+
+    fn total_impl(shapes: &[impl Shape]) -> f64 {
+        shapes.iter().map(|shape| shape.area()).sum()
+    }
+
+    fn total_dyn(shapes: &[Box<dyn Shape>]) -> f64 {
+        shapes.iter().map(|shape| shape.area()).sum()
+    }
+
+    let squares = [Square(1.0), Square(2.0)];
+
+    // generic and `impl Trait` versions only accept one concrete type per call
+    assert_eq!(total_generic(&squares), 5.0);
+    assert_eq!(total_impl(&squares), 5.0);
+
+    // `total_generic(&[Square(1.0), Circle(1.0)])` -> Error: mismatched types
+
+    // the trait object version accepts different types in the same slice
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Square(1.0)),
+        Box::new(Square(2.0)),
+        Box::new(Circle(1.0)),
+    ];
+
+    assert_eq!(total_dyn(&shapes), 5.0 + std::f64::consts::PI);
+
+    // `impl Trait` in return position hides the concrete type but is still static dispatch -> only one type can be returned
+    fn unit_shape() -> impl Shape {
+        Square(1.0)
+    }
+
+    // returning different types requires a trait object
+    fn shape(round: bool) -> Box<dyn Shape> {
+        if round {
+            Box::new(Circle(1.0))
+        } else {
+            Box::new(Square(1.0))
+        }
+    }
+
+    assert_eq!(unit_shape().area(), shape(false).area());
+}
+
+/// Every closure has its own anonymous type, even two closures with the same code. `dyn FnMut(&T)` erases the type,
+/// so different closures fit into one collection like the shapes above: [`Callbacks`](crate::functions::Callbacks) is a `Vec<Box<dyn FnMut(&T) + 'a>>`.
+/// The `+ 'a` bounds what the closures may borrow, without it a `Box<dyn ..>` defaults to `'static` and can't borrow local variables.
+#[test]
+fn closures_as_trait_objects() {
+    use crate::functions::Callbacks;
+
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Square { side: 1.0 }),
+        Box::new(Square { side: 2.0 }),
+        Box::new(Circle { radius: 1.0 }),
+    ];
+
+    let mut total = 0.0;
+    let mut largest: f64 = 0.0;
+    let mut count = 0;
+
+    {
+        let mut callbacks = Callbacks::new(); // <- three closures of three different types
+
+        callbacks.register(|area: &f64| total += area);
+        callbacks.register(|area| largest = largest.max(*area));
+        callbacks.register(|_| count += 1);
+
+        for shape in &shapes {
+            callbacks.notify(&shape.area()); // <- `area` through the vtable of the shape, every callback through the vtable of its closure
+        }
+    }
+
+    assert_eq!(total, total_area(&shapes));
+    assert_eq!(largest, 4.0); // <- the square with side 2
+    assert_eq!(count, 3);
+
+    // a boxed closure is a fat pointer like a boxed shape: data (the captured variables) and vtable
+    assert_eq!(
+        std::mem::size_of::<Box<dyn FnMut(&f64)>>(),
+        2 * std::mem::size_of::<usize>()
+    );
+}
+
+/// A supertrait is a trait that has to be implemented for a type to implement another trait: `trait Metallic: Element`.
+/// Methods of the supertrait can be used in the default implementations of the trait and can be called on its trait objects.
+/// A trait object of the trait can be converted to a trait object of the supertrait (trait upcasting).
+#[test]
+fn supertraits() {
+    trait Element {
+        fn symbol(&self) -> &'static str;
+    }
+
+    trait Metallic: Element {
+        fn melting_point(&self) -> f32;
+
+        fn describe(&self) -> String {
+            format!("{} melts at {} °C", self.symbol(), self.melting_point()) // supertrait method is available
+        }
+    }
+
+    struct Iron;
+
+    impl Element for Iron {
+        fn symbol(&self) -> &'static str {
+            "Fe"
+        }
+    }
+
+    impl Metallic for Iron {
+        fn melting_point(&self) -> f32 {
+            1538.0
+        }
+    }
+
+    // `impl Metallic for Carbon {}` -> Error: the trait bound `Carbon: Element` is not satisfied
+
+    let metal: Box<dyn Metallic> = Box::new(Iron);
+
+    assert_eq!(metal.symbol(), "Fe"); // the vtable of `dyn Metallic` contains the methods of `Element` as well
+    assert_eq!(metal.describe(), "Fe melts at 1538 °C");
+
+    let element: &dyn Element = metal.as_ref(); // trait upcasting
+
+    assert_eq!(element.symbol(), "Fe");
+}
@@ -5,19 +5,49 @@
 //! The `Result` enum is typically used when more than one thing could go wrong. `Option` is used when something could be something or nothing.
 //! Functionally `Result<T, E>` and `Option<T>` are very similar.
 
-#[test]
-fn error_handling() {
-    use std::error::Error;
+/// Parses an atomic weight, e.g. `"55.845"` for iron.
+/// The error of `str::parse` is passed on to the caller with `?` instead of panicking.
+///
+/// ```
+/// use rust_snippets::errror_handling::parse_weight;
+///
+/// # fn main() -> Result<(), std::num::ParseFloatError> {
+/// let iron = parse_weight(" 55.845 ")?; // `?` also works in examples, if they return a `Result`
+///
+/// assert_eq!(iron, 55.845);
+/// assert!(parse_weight("heavy").is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// `unwrap` turns the error into a panic:
+///
+/// ```should_panic
+/// rust_snippets::errror_handling::parse_weight("heavy").unwrap(); // panics: invalid float literal
+/// ```
+pub fn parse_weight(input: &str) -> Result<f32, std::num::ParseFloatError> {
+    let weight = input.trim().parse::<f32>()?;
 
-    fn mult_add(a: u8, b: u8) -> Result<u8, Box<dyn Error>> {
-        let x = a
-            .checked_add(b)
-            .ok_or("Overflow while adding".into())
-            .and_then(|x| x.checked_mul(b).ok_or("Overflow while multiplying".into()));
+    Ok(weight)
+}
 
-        x
-    }
+/// Computes `(a + b) * b` and returns an error instead of overflowing.
+/// `checked_add` and `checked_mul` return `None` on overflow, `ok_or` turns the `None` into an error and `and_then` chains the steps.
+///
+/// ```
+/// use rust_snippets::errror_handling::mult_add;
+///
+/// assert_eq!(mult_add(1, 2).unwrap(), 6);
+/// assert_eq!(mult_add(16, 16).unwrap_err().to_string(), "Overflow while multiplying");
+/// ```
+pub fn mult_add(a: u8, b: u8) -> Result<u8, Box<dyn std::error::Error>> {
+    a.checked_add(b)
+        .ok_or("Overflow while adding".into())
+        .and_then(|x| x.checked_mul(b).ok_or("Overflow while multiplying".into()))
+}
 
+#[test]
+fn error_handling() {
     let result = mult_add(1, 2); // -> Result<u8, Box<dyn Error>> NOT u8
 
     // let y = x + 1; // -> error: cannot add `{integer}` to `Result<u8, Box<dyn std::error::Error>>`
@@ -53,11 +83,15 @@ fn error_handling() {
 /// This makes runtime errors in Python harder to debug than in Rust and significanly more common.
 /// In Rust, the compiler will not let you forget to handle an error. In Python, you have to remember to handle the error.
 /// This does make the code more verbose though.
+///
+/// The harness (`harness/python.rs`) runs the Python version of each step and compares it with the Rust version.
 #[cfg(feature = "python")]
 #[test]
 fn error_handling_vs_python() {
+    use crate::harness::python::{compare, run_python_with, run_rust, Expected, Outcome, Value};
     use indoc::indoc;
     use std::error::Error;
+    use std::fmt::Write;
 
     fn get_file_content(file_name: &str) -> Result<String, Box<dyn Error>> {
         let list_of_files = ["file1", "file3"];
@@ -75,55 +109,82 @@ fn error_handling_vs_python() {
 
     assert_eq!(file_content, "content1");
 
-    // `match`` to handle the error -> manual error handling
-    let file_content: String = match get_file_content("file2") {
-        Ok(content) => content,
-        Err(error) => {
-            println!(
-                "Problem opening the file: {} defaulting to empty String",
-                error
-            );
-            "".to_string()
-        }
-    };
-
-    assert_eq!(file_content, "");
-
-    // boilerplate to run python code
-    pyo3::prepare_freethreaded_python();
-
-    pyo3::Python::with_gil(|py| -> pyo3::PyResult<()> {
-        py.run(
-            indoc! {  // indoc macro to write formatted multiline strings
+    let python = |file_name: &str| {
+        run_python_with(
+            indoc! {
                 "
                 def get_file_content(file_name):
-                        if file_name == 'file1':
-                            return 'content1'
-                        elif file_name == 'file2':
-                            return 'content2'
-                        else:
-                            raise Exception('file not found')
+                    if file_name in ['file1', 'file3']:
+                        return 'content' + file_name[-1]
+                    else:
+                        raise Exception('file not found')
 
-                file_content = get_file_content('file1')  # -> no explicit error handling required
+                result = get_file_content(file_name)  # -> no explicit error handling required
+                "
+            },
+            |_, variables| variables.set_item("file_name", file_name),
+        )
+    };
 
-                assert file_content == 'content1'
+    compare(python("file1"), run_rust(|_| get_file_content("file1"))).assert_equivalent();
+
+    // it is not apparent from the Python code that `get_file_content` can raise an exception, the Rust signature returns a `Result`
+    compare(python("file2"), run_rust(|_| get_file_content("file2"))).assert_diverges(
+        Expected::Fails("Exception: file not found"), // <- Python raises, the caller did nothing to handle it
+        Expected::Fails("file not found"), // <- Rust returns an `Err`, the caller has to look at it to get the content
+    );
+
+    // `match` to handle the error -> manual error handling
+    let rust = |file_name: &str| {
+        run_rust(|stdout| {
+            let file_content = match get_file_content(file_name) {
+                Ok(content) => content,
+                Err(error) => {
+                    writeln!(stdout, "Problem opening the file: {error}")?;
+                    "".to_string()
+                }
+            };
+
+            Ok::<_, std::fmt::Error>(file_content)
+        })
+    };
 
-                # file_content = get_file_content('file4')  # -> Exception: file not found | it is not apparent from the code that this function can raise an exception
+    // try except block is opt-in to handle the error
+    let python = |file_name: &str| {
+        run_python_with(
+            indoc! {
+                "
+                def get_file_content(file_name):
+                    if file_name in ['file1', 'file3']:
+                        return 'content' + file_name[-1]
+                    else:
+                        raise Exception('file not found')
 
-                # try except block is opt-in to handle the error
                 try:
-                    file_content = get_file_content('file1')
+                    result = get_file_content(file_name)
                 except Exception as e:
                     print('Problem opening the file: {}'.format(e))
+                    result = ''
                 "
             },
-            None,
-            None,
-        )?;
+            |_, variables| variables.set_item("file_name", file_name),
+        )
+    };
 
-        Ok(())
-    })
-    .unwrap();
+    for file_name in ["file1", "file2"] {
+        compare(python(file_name), rust(file_name)).assert_equivalent(); // <- handled, both print the problem and return ''
+    }
+
+    let file_content = rust("file2");
+
+    assert_eq!(
+        file_content.stdout,
+        "Problem opening the file: file not found\n"
+    );
+    assert_eq!(
+        file_content.outcome,
+        Outcome::Returned(Value::Str("".to_string()))
+    );
 }
 
 /// https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html?#propagating-errors
@@ -226,3 +287,22 @@ fn question_mark_operator() {
         }
     };
 }
+
+/// The error handling of `mult_add` for every input, not just for `(1, 2)` and `(16, 16)`.
+#[test]
+fn error_handling_properties() {
+    use crate::property_testing::check;
+    use proptest::prelude::*;
+
+    // property: `Ok` with the exact result if it fits into a `u8`, an error otherwise
+    check(&(any::<u8>(), any::<u8>()), |(a, b)| {
+        let exact = (a as u32 + b as u32) * b as u32; // `u32` can hold the exact result for any two `u8`
+
+        match mult_add(a, b) {
+            Ok(x) => prop_assert_eq!(x as u32, exact),
+            Err(_) => prop_assert!(exact > u8::MAX as u32),
+        }
+
+        Ok(())
+    });
+}
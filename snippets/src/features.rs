@@ -0,0 +1,176 @@
+//! https://doc.rust-lang.org/cargo/reference/features.html
+//! https://doc.rust-lang.org/reference/conditional-compilation.html
+//!
+//! Conditional compilation includes or removes code depending on configuration options, e.g. the target OS or a cargo feature.
+//! Code that is configured away is removed before type checking: it does not have to compile and it costs nothing.
+//!
+//! Features are declared in `Cargo.toml` and enabled with `--features`:
+//!
+//! ```toml
+//! [features]
+//! cc = ["dep:cc"]                       # enables the optional dependency `cc` (also the build-dependency, see build.rs)
+//! python = ["dep:pyo3", "dep:numpy"]    # enables two optional dependencies
+//! ```
+//!
+//! This crate gates the lessons that need a C or C++ compiler (`cpp.rs`, `ffi.rs`, `safety.rs`) behind `cc` and the ones that need Python (`pyo3.rs`, `numpy.rs`) behind `python`.
+//! `cargo xtask features` builds and tests every combination of the features and reports which lessons run in each.
+//!
+//! Features have to be additive: enabling a feature may only add code, never remove or change existing behaviour.
+//! Cargo unifies the features of a dependency across the whole dependency graph, if one crate enables `python`
+//! every other crate that depends on `rust_snippets` gets it too. A feature like `no_python` that removes code would break crates that rely on it.
+//!
+//! Code behind a disabled feature is not compiled, so errors in it go unnoticed until somebody enables the feature:
+//!
+//! ```compile_fail,E0425
+//! #[cfg(all())] // <- always true, like an enabled feature
+//! fn enabled() -> i32 {
+//!     undefined_function() // <- cannot find function `undefined_function` in this scope
+//! }
+//!
+//! #[cfg(any())] // <- always false, like a disabled feature
+//! fn disabled() -> i32 {
+//!     undefined_function() // <- never compiled, no error
+//! }
+//! ```
+
+/// `#[cfg(..)]` removes the item it is attached to if the predicate is false.
+/// Predicates are options (`test`, `debug_assertions`, `unix`), key-value pairs (`feature = "cc"`, `target_os = "linux"`)
+/// and their combinations with `all(..)`, `any(..)` and `not(..)`.
+#[test]
+fn cfg_attribute() {
+    #[cfg(target_pointer_width = "64")]
+    const POINTER_BITS: u32 = 64;
+
+    #[cfg(target_pointer_width = "32")]
+    const POINTER_BITS: u32 = 32;
+
+    #[cfg(not(any(target_pointer_width = "64", target_pointer_width = "32")))]
+    const POINTER_BITS: u32 = 16; // <- only one of the three definitions exists, no "defined multiple times" error
+
+    assert_eq!(POINTER_BITS, usize::BITS);
+
+    #[cfg(feature = "cc")]
+    fn lessons_with_c() -> &'static str {
+        "cpp.rs, ffi.rs, safety.rs"
+    }
+
+    #[cfg(not(feature = "cc"))]
+    fn lessons_with_c() -> &'static str {
+        "none"
+    }
+
+    // exactly one of the functions is compiled, which one depends on `--features`
+    let expected = if cfg!(feature = "cc") {
+        "cpp.rs, ffi.rs, safety.rs"
+    } else {
+        "none"
+    };
+
+    assert_eq!(lessons_with_c(), expected);
+
+    // `cfg` works on statements and on elements of lists too
+    let enabled: Vec<&str> = vec![
+        #[cfg(feature = "cc")]
+        "cc",
+        #[cfg(feature = "python")]
+        "python",
+    ];
+
+    assert_eq!(enabled.contains(&"cc"), cfg!(feature = "cc"));
+    assert_eq!(enabled.contains(&"python"), cfg!(feature = "python"));
+}
+
+/// `cfg!(..)` evaluates the same predicate to a `bool` at compile time.
+/// Unlike `#[cfg]` both branches are compiled (and have to type check), the optimizer removes the dead one.
+/// It can only be used if the code in both branches exists in every configuration.
+#[test]
+fn cfg_macro() {
+    let build = if cfg!(debug_assertions) {
+        "debug" // <- `cargo test`
+    } else {
+        "release" // <- `cargo test --release`
+    };
+
+    assert_eq!(build == "debug", cfg!(debug_assertions));
+
+    let harness = if cfg!(test) { "test" } else { "main" }; // <- `test` is set when compiling with `--test` (`cargo test`)
+
+    assert_eq!(harness, "test");
+
+    // if the other branch calls code that only exists with a feature, `cfg!` does not compile without that feature:
+    // if cfg!(feature = "python") { pyo3::prepare_freethreaded_python() } // Error: failed to resolve: use of undeclared crate `pyo3`
+}
+
+/// `#[cfg_attr(predicate, attribute)]` applies the attribute only if the predicate is true.
+/// Typical uses are derives for optional dependencies (`#[cfg_attr(feature = "serde", derive(Serialize))]`),
+/// lints that only apply in some configurations and ignoring tests that need something unavailable.
+#[test]
+fn cfg_attr() {
+    #[cfg_attr(test, derive(Debug, PartialEq))] // <- `Debug` and `PartialEq` are only needed by the assertions in tests
+    struct Molecule {
+        name: &'static str,
+    }
+
+    let water = Molecule { name: "Water" };
+
+    assert_eq!(water, Molecule { name: "Water" });
+
+    #[cfg_attr(not(feature = "python"), allow(dead_code))] // <- only used with `python`, no warning without it
+    fn python_lessons() -> [&'static str; 2] {
+        ["pyo3.rs", "numpy.rs"]
+    }
+
+    #[cfg(feature = "python")]
+    assert_eq!(python_lessons().len(), 2);
+}
+
+/// `cfg_attr` can ignore a test instead of removing it: `cargo test` lists it as ignored with the reason,
+/// so it is visible that the lesson exists but didn't run. The body still has to compile without the feature.
+#[test]
+#[cfg_attr(not(feature = "cc"), ignore = "needs a C compiler (--features cc)")]
+fn ignored_without_cc() {
+    #[cfg(feature = "cc")]
+    {
+        extern "C" {
+            fn addc(a: i32, b: i32) -> i32;
+        }
+
+        // SAFETY: `addc` is compiled from c/src/ffi.c by build.rs and has this signature
+        assert_eq!(unsafe { addc(1, 2) }, 3); // <- the C library `ffi` is only linked if build.rs compiled it
+    }
+}
+
+/// Features are visible to build scripts through the environment variables `CARGO_FEATURE_<NAME>`, and to `#[cfg]` in build.rs.
+/// build.rs passes the ones it saw on to the crate (`cargo:rustc-env=BUILD_SCRIPT_FEATURES=cc,python`), so they can be compared with `cfg!`.
+/// A build script is a separate crate with its own dependencies (`[build-dependencies]`).
+/// build.rs uses `cc` to compile `c/src/ffi.c`, so the `cc` feature has to enable the optional build-dependency as well:
+/// `cc = ["dep:cc"]` enables both, because the normal and the build-dependency have the same name.
+/// If only the normal dependency was enabled, `cargo build --features cc` would fail with "use of undeclared crate `cc`" in build.rs.
+/// The feature matrix (`cargo xtask features`) builds every combination and catches mistakes like this.
+#[test]
+fn build_script_features() {
+    let features: Vec<&str> = env!("BUILD_SCRIPT_FEATURES")
+        .split(',')
+        .filter(|feature| !feature.is_empty())
+        .collect();
+
+    // the build script and the crate see the same features
+    assert_eq!(features.contains(&"cc"), cfg!(feature = "cc"));
+    assert_eq!(features.contains(&"python"), cfg!(feature = "python"));
+    assert_eq!(
+        features.contains(&"python-extension"),
+        cfg!(feature = "python-extension")
+    );
+
+    // with `cc` build.rs ran the `cc` build-dependency: it compiled the `ffi` library and passed on the target for the C harness
+    #[cfg(feature = "cc")]
+    {
+        extern "C" {
+            fn addc(a: i32, b: i32) -> i32;
+        }
+
+        // SAFETY: `addc` is compiled from c/src/ffi.c by build.rs and has this signature
+        assert_eq!(unsafe { addc(2, 3) }, 5);
+        assert!(!env!("TARGET").is_empty());
+    }
+}
@@ -5,6 +5,206 @@
 //! 
 //! The datatype of the arguments has to be explicitly specified (i32, i64, f32, f64, str, Vec<_> ..)
 //! The return type has to be explicitly specified except for the unit type `()`.
+//!
+//! https://doc.rust-lang.org/book/ch13-01-closures.html
+//!
+//! Closures implement one or more of three traits, depending on what they do with the values they capture:
+//!
+//! - `Fn`: only reads its captures, can be called any number of times, also from several places at once
+//! - `FnMut`: changes its captures, can be called any number of times but only through a `&mut` (one caller at a time)
+//! - `FnOnce`: moves a capture out (drops it, returns it, ..), can only be called once
+//!
+//! Every `Fn` is also `FnMut` and every `FnMut` is also `FnOnce`, so `FnOnce` is the least demanding bound for a caller.
+//! A closure that changes its captures can't be passed where `Fn` is required:
+//!
+//! ```compile_fail,E0525
+//! use rust_snippets::functions::apply;
+//!
+//! let mut calls = 0;
+//!
+//! let add = |a: i32, b: i32| {
+//!     calls += 1; // <- changes `calls`, so the closure is only `FnMut`
+//!     a + b
+//! };
+//!
+//! apply(add, 1, 2); // <- expected a closure that implements the `Fn` trait, but this closure only implements `FnMut`
+//! ```
+//!
+//! and a closure that moves a capture out can't be called twice:
+//!
+//! ```compile_fail,E0382
+//! let name = String::from("iron");
+//!
+//! let consume = move || name; // <- returns `name`, so the closure is only `FnOnce`
+//!
+//! consume();
+//! consume(); // <- use of moved value: `consume`
+//! ```
+
+/// Calls `f` with `a` and `b`. Functions, function pointers and closures can all be passed as `f`, because all of them implement `Fn`.
+///
+/// ```
+/// use rust_snippets::functions::apply;
+///
+/// fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// assert_eq!(apply(add, 1, 2), 3); // a function
+/// assert_eq!(apply(|a, b| a * b, 3, 4), 12); // a closure
+///
+/// let offset = 10;
+///
+/// assert_eq!(apply(|a, b| a + b + offset, 1, 2), 13); // a closure that captures `offset`
+/// ```
+pub fn apply<F>(f: F, a: i32, b: i32) -> i32
+where
+    F: Fn(i32, i32) -> i32,
+{
+    f(a, b)
+}
+
+/// Returns a closure that adds `n`. `impl Fn` hides the type of the closure (every closure has its own unnamed type),
+/// the caller only knows that it can be called. It's returned by value, no allocation.
+///
+/// The closure has to `move` `n` into itself, a reference to `n` would outlive the function:
+///
+/// ```compile_fail,E0373
+/// fn adder(n: i32) -> impl Fn(i32) -> i32 {
+///     |x| x + n // <- closure may outlive the current function, but it borrows `n`
+/// }
+/// ```
+///
+/// ```
+/// use rust_snippets::functions::adder;
+///
+/// let add_two = adder(2);
+///
+/// assert_eq!(add_two(1), 3);
+/// assert_eq!([1, 2, 3].map(adder(10)), [11, 12, 13]);
+/// ```
+pub fn adder(n: i32) -> impl Fn(i32) -> i32 {
+    move |x| x + n
+}
+
+/// Returns the closure for an operator. `impl Fn` is one concrete type, but every closure has a different one,
+/// so returning one of several closures needs a trait object: `Box<dyn Fn>` (allocated, called through a vtable).
+///
+/// ```compile_fail,E0308
+/// fn scale(symbol: char, factor: i32) -> impl Fn(i32) -> i32 {
+///     match symbol {
+///         '*' => move |x| x * factor,
+///         _ => move |x| x / factor, // <- `match` arms have incompatible types, no two closures have the same type
+///     }
+/// }
+/// ```
+///
+/// Closures that capture nothing (like the ones below) can also be coerced to the same function pointer type `fn(i32, i32) -> i32`,
+/// `Box<dyn Fn>` works for capturing closures too.
+///
+/// ```
+/// use rust_snippets::functions::{apply, operation};
+///
+/// let multiply = operation('*').unwrap();
+///
+/// assert_eq!(multiply(3, 4), 12);
+/// assert_eq!(apply(operation('-').unwrap(), 3, 4), -1); // <- `Box<dyn Fn>` implements `Fn` too
+/// assert!(operation('?').is_none());
+/// ```
+pub fn operation(symbol: char) -> Option<Box<dyn Fn(i32, i32) -> i32>> {
+    match symbol {
+        '+' => Some(Box::new(|a, b| a + b)),
+        '-' => Some(Box::new(|a, b| a - b)),
+        '*' => Some(Box::new(|a, b| a * b)),
+        '/' => Some(Box::new(|a, b| a / b)),
+        _ => None,
+    }
+}
+
+/// Identifies a registered callback, to unregister it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallbackId(usize);
+
+type Callback<'a, T> = Box<dyn FnMut(&T) + 'a>;
+
+/// A registry of callbacks that are called with every event, e.g. to log or count what happens in a lesson
+/// (`dyn_dispatch.rs` collects statistics about shapes with it, `process.rs` streams the output of a program into it).
+///
+/// The callbacks are `FnMut` (the least demanding bound that still allows several calls) stored as trait objects,
+/// so closures of different types fit into one `Vec`. They may borrow from their environment for `'a`.
+///
+/// ```
+/// use rust_snippets::functions::Callbacks;
+///
+/// let mut log = Vec::new();
+/// let mut count = 0;
+///
+/// {
+///     let mut callbacks = Callbacks::new();
+///
+///     callbacks.register(|element: &&str| log.push(element.to_string())); // <- borrows `log` mutably
+///     let counter = callbacks.register(|_| count += 1);
+///
+///     callbacks.notify(&"iron");
+///     callbacks.unregister(counter);
+///     callbacks.notify(&"gold");
+/// } // <- the callbacks and their borrows end here
+///
+/// assert_eq!(log, ["iron", "gold"]);
+/// assert_eq!(count, 1);
+/// ```
+pub struct Callbacks<'a, T> {
+    callbacks: Vec<(CallbackId, Callback<'a, T>)>,
+    next_id: usize,
+}
+
+impl<'a, T> Callbacks<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            callbacks: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn register(&mut self, callback: impl FnMut(&T) + 'a) -> CallbackId {
+        let id = CallbackId(self.next_id);
+
+        self.next_id += 1;
+        self.callbacks.push((id, Box::new(callback)));
+
+        id
+    }
+
+    /// Returns `false` if the callback was not registered (anymore).
+    pub fn unregister(&mut self, id: CallbackId) -> bool {
+        let len = self.callbacks.len();
+
+        self.callbacks.retain(|(registered, _)| *registered != id);
+
+        self.callbacks.len() != len
+    }
+
+    /// Calls the callbacks in the order they were registered.
+    pub fn notify(&mut self, event: &T) {
+        for (_, callback) in &mut self.callbacks {
+            callback(event);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.callbacks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+}
+
+impl<T> Default for Callbacks<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[test]
 fn function() {
@@ -64,4 +264,173 @@ fn function_argument() {
     assert_eq!(apply(add, 1, 2), 3);
 }
 
+/// Which trait a closure implements is decided by what its body does with the captures, not by how it's declared.
+/// A function that takes a closure chooses the bound it needs: `Fn` to call it through a shared reference,
+/// `FnMut` to call it several times, `FnOnce` to call it once.
+#[test]
+fn closure_traits() {
+    fn call_fn(f: impl Fn() -> usize) -> usize {
+        f() + f()
+    }
+
+    fn call_fn_mut(mut f: impl FnMut() -> usize) -> usize {
+        f() + f()
+    }
+
+    fn call_fn_once(f: impl FnOnce() -> usize) -> usize {
+        f()
+    }
+
+    let elements = vec!["hydrogen", "helium"];
+
+    let count = || elements.len(); // <- only reads `elements` -> Fn
+
+    assert_eq!(call_fn(count), 4);
+    assert_eq!(call_fn_mut(count), 4); // <- an `Fn` is also `FnMut`
+    assert_eq!(call_fn_once(count), 2); // <- and `FnOnce`
+
+    let mut calls = 0;
+
+    let mut count_calls = || {
+        calls += 1; // <- changes `calls` -> FnMut
+        calls
+    };
+
+    // call_fn(count_calls); // -> error: expected a closure that implements the `Fn` trait, but this closure only implements `FnMut`
+    assert_eq!(call_fn_mut(&mut count_calls), 1 + 2); // <- `&mut F` is `FnMut` too, `count_calls` is only borrowed
+    assert_eq!(count_calls(), 3);
 
+    let consume = move || {
+        let elements = elements; // <- moves `elements` out of the closure -> FnOnce
+        elements.len()
+    };
+
+    // call_fn_mut(consume); // -> error: expected a closure that implements the `FnMut` trait, but this closure only implements `FnOnce`
+    assert_eq!(call_fn_once(consume), 2);
+}
+
+/// A closure captures by reference when it can and by value when it has to (e.g. when it returns a capture).
+/// `move` makes it capture everything by value, which is needed when the closure outlives the scope it's created in,
+/// e.g. a thread that may run longer than the function that spawned it.
+#[test]
+fn move_closures() {
+    let elements = Vec::from(["hydrogen", "helium", "lithium"]); // <- a `Vec` is moved, an array of `&str` would be copied
+
+    // std::thread::spawn(|| elements.len()); // -> error: closure may outlive the current function, but it borrows `elements`
+
+    let handle = std::thread::spawn(move || elements.len()); // <- `elements` is moved into the thread
+
+    // println!("{elements:?}"); // -> error: borrow of moved value: `elements`
+    assert_eq!(handle.join().unwrap(), 3);
+
+    // `move` only moves, it doesn't copy: clone before to keep a copy
+    let elements = Vec::from(["hydrogen", "helium", "lithium"]);
+    let copy = elements.clone();
+
+    let handle = std::thread::spawn(move || copy.join(", "));
+
+    assert_eq!(handle.join().unwrap(), "hydrogen, helium, lithium");
+    assert_eq!(elements.len(), 3);
+
+    // scoped threads are joined before the scope ends, so they can borrow without `move`
+    let mut lengths = Vec::new();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| lengths.extend(elements.iter().map(|element| element.len())));
+    });
+
+    assert_eq!(lengths, [8, 6, 7]);
+}
+
+/// A struct can store a closure as a generic field (`F: Fn(..)`, one concrete type, no allocation)
+/// or as a trait object (`Box<dyn Fn(..)>`, see `Callbacks`). This cache calls its function once per input.
+#[test]
+fn closures_in_structs() {
+    use std::collections::HashMap;
+
+    struct Cache<F: Fn(u64) -> u64> {
+        function: F,
+        values: HashMap<u64, u64>,
+    }
+
+    impl<F: Fn(u64) -> u64> Cache<F> {
+        fn new(function: F) -> Self {
+            Self {
+                function,
+                values: HashMap::new(),
+            }
+        }
+
+        fn get(&mut self, input: u64) -> u64 {
+            // `(self.function)(..)`, `self.function(..)` would look for a method called `function`
+            *self
+                .values
+                .entry(input)
+                .or_insert_with(|| (self.function)(input))
+        }
+    }
+
+    let calls = std::cell::Cell::new(0);
+
+    let mut squares = Cache::new(|x| {
+        calls.set(calls.get() + 1); // <- a `Cell` can be changed from an `Fn` closure
+        x * x
+    });
+
+    assert_eq!(squares.get(4), 16);
+    assert_eq!(squares.get(4), 16);
+    assert_eq!(squares.get(5), 25);
+    assert_eq!(calls.get(), 2);
+}
+
+/// `variables.rs` stores a closure in a constant: `const CL: &'static dyn Fn(u32) -> u32 = &|x: u32| x + 1;`
+///
+/// - the type of a closure has no name, so the constant needs a trait object (`dyn Fn`) behind a reference
+/// - `&|..| ..` is promoted to a `'static` value (like `&1`), because the closure captures nothing and is a constant expression
+/// - calling it is not a constant expression (`const_assert_eq!(CL(1), 2)` fails), closures can't be `const fn`s
+///
+/// A closure that captures nothing can also be coerced to a function pointer, which needs no reference.
+#[test]
+fn constant_closures() {
+    const CL: &dyn Fn(u32) -> u32 = &|x: u32| x + 1;
+    const POINTER: fn(u32) -> u32 = |x| x + 1; // <- coerced to `fn(u32) -> u32`
+
+    // const CAPTURE: &dyn Fn(u32) -> u32 = &|x: u32| x + OFFSET; // <- fine for a constant `OFFSET`, but a local `let offset` can't be captured in a constant
+
+    assert_eq!(CL(1), 2);
+    assert_eq!(POINTER(1), 2);
+    assert_eq!([1, 2].map(POINTER), [2, 3]);
+}
+
+/// `Callbacks` stores closures of different types, each borrowing something else from the test.
+#[test]
+fn callbacks() {
+    use std::cell::RefCell;
+
+    let log = RefCell::new(Vec::new()); // <- shared by two callbacks, each one only borrows it while it runs
+    let mut total = 0;
+
+    {
+        let mut callbacks = Callbacks::new();
+
+        callbacks.register(|weight: &f32| log.borrow_mut().push(format!("{weight}")));
+        callbacks.register(|weight| total += *weight as u32);
+        let heavy = callbacks.register(|weight| {
+            if *weight > 100.0 {
+                log.borrow_mut().push("heavy".to_string())
+            }
+        });
+
+        callbacks.notify(&55.845);
+        callbacks.notify(&196.97);
+
+        assert!(callbacks.unregister(heavy));
+        assert!(!callbacks.unregister(heavy));
+        assert_eq!(callbacks.len(), 2);
+
+        callbacks.notify(&107.87);
+    }
+
+    assert_eq!(log.into_inner(), ["55.845", "196.97", "heavy", "107.87"]);
+    assert_eq!(total, 55 + 196 + 107);
+}
@@ -1,6 +1,133 @@
 //! Generics are a way to loosen the type restrictions of a function or type without losing type safety.
 //!
 //! In addition to `Traits` sharing functionality between types, they are used to define what functionality a generic type has to implement to be used in a generic function or type.
+//!
+//! The type system rejects the following misuses at compile time.
+//!
+//! A trait with an associated type can only be implemented once per type:
+//!
+//! ```compile_fail,E0119
+//! struct Countdown(u32);
+//!
+//! impl Iterator for Countdown {
+//!     type Item = u32;
+//!     fn next(&mut self) -> Option<u32> { None }
+//! }
+//!
+//! impl Iterator for Countdown {
+//!     type Item = i64;
+//!     fn next(&mut self) -> Option<i64> { None }
+//! }
+//! ```
+//!
+//! The length of an array is part of its type:
+//!
+//! ```compile_fail,E0308
+//! fn sum<const N: usize>(values: [i32; N]) -> i32 {
+//!     values.iter().sum()
+//! }
+//!
+//! sum::<3>([1, 2]);
+//! ```
+//!
+//! A type that is covered by a blanket implementation can't implement the trait again:
+//!
+//! ```compile_fail,E0119
+//! trait Describe {
+//!     fn describe(&self) -> String;
+//! }
+//!
+//! impl<T: std::fmt::Debug> Describe for T {
+//!     fn describe(&self) -> String { format!("{self:?}") }
+//! }
+//!
+//! impl Describe for i32 {
+//!     fn describe(&self) -> String { self.to_string() }
+//! }
+//! ```
+//!
+//! A function that only accepts one specific lifetime does not satisfy a higher-ranked trait bound:
+//!
+//! ```compile_fail,E0308
+//! fn first_word_length<F>(f: F) -> usize
+//! where
+//!     F: for<'a> Fn(&'a str) -> &'a str,
+//! {
+//!     f(&String::from("hello world")).len()
+//! }
+//!
+//! fn only_static(s: &'static str) -> &'static str {
+//!     s
+//! }
+//!
+//! first_word_length(only_static);
+//! ```
+//!
+//! Methods of a typestate builder only exist in the right state:
+//!
+//! ```compile_fail,E0599
+//! use std::marker::PhantomData;
+//!
+//! struct NoUrl;
+//! struct HasUrl;
+//! struct RequestBuilder<State>(PhantomData<State>);
+//!
+//! impl RequestBuilder<HasUrl> {
+//!     fn build(self) {}
+//! }
+//!
+//! RequestBuilder::<NoUrl>(PhantomData).build();
+//! ```
+//!
+//! Values with different phantom units can't be mixed:
+//!
+//! ```compile_fail,E0308
+//! use std::marker::PhantomData;
+//!
+//! struct Meters;
+//! struct Feet;
+//! struct Length<Unit>(f64, PhantomData<Unit>);
+//!
+//! let feet: Length<Feet> = Length(3.0, PhantomData);
+//! let meters: Length<Meters> = feet;
+//! ```
+//!
+//! Orphan rule -> a foreign trait can't be implemented for a foreign type:
+//!
+//! ```compile_fail,E0117
+//! impl std::fmt::Display for Vec<String> {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         write!(f, "{}", self.join(", "))
+//!     }
+//! }
+//! ```
+
+/// Returns the largest element of a slice for any element type that can be compared (`PartialOrd`) and copied out of the slice (`Copy`).
+///
+/// ```
+/// use rust_snippets::generics::largest;
+///
+/// assert_eq!(largest(&[3, 7, 2]), 7);
+/// assert_eq!(largest(&[0.5, -1.0]), 0.5);
+/// assert_eq!(largest(&['a', 'z', 'q']), 'z');
+/// ```
+///
+/// An empty slice has no largest element:
+///
+/// ```should_panic
+/// rust_snippets::generics::largest::<i32>(&[]); // panics: the slice is empty
+/// ```
+pub fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
+    let mut largest = *list.first().expect("the slice is empty");
+
+    for &item in list {
+        if item > largest {
+            largest = item;
+        }
+    }
+
+    largest
+}
 
 #[test]
 fn generics() {
@@ -46,3 +173,362 @@ fn generics() {
 
     assert_eq!(t, "Yes");
 }
+
+/// A trait can be generic over a type `trait Convert<T>` or have an associated type `trait Iterator { type Item; }`.
+/// A generic trait can be implemented many times for the same type (once for every `T`) -> the caller picks the implementation.
+/// An associated type is chosen once per implementation -> the implementing type determines it and the caller doesn't have to annotate it.
+/// See the module documentation for implementing a trait with an associated type twice.
+#[test]
+fn associated_types() {
+    // generic parameter -> `Celsius` can be converted into many types
+    trait Convert<T> {
+        fn convert(&self) -> T;
+    }
+
+    struct Celsius(f64);
+    struct Fahrenheit(f64);
+    struct Kelvin(f64);
+
+    impl Convert<Fahrenheit> for Celsius {
+        fn convert(&self) -> Fahrenheit {
+            Fahrenheit(self.0 * 9.0 / 5.0 + 32.0)
+        }
+    }
+
+    impl Convert<Kelvin> for Celsius {
+        fn convert(&self) -> Kelvin {
+            Kelvin(self.0 + 273.15)
+        }
+    }
+
+    let boiling = Celsius(100.0);
+
+    // the caller has to pick the implementation with a type annotation
+    let f: Fahrenheit = boiling.convert();
+    let k: Kelvin = boiling.convert();
+
+    assert_eq!(f.0, 212.0);
+    assert_eq!(k.0, 373.15);
+
+    // associated type -> a `Countdown` has exactly one `Item` type
+    trait Sequence {
+        type Item; // the implementation decides what `Item` is
+
+        fn next_item(&mut self) -> Option<Self::Item>;
+    }
+
+    struct Countdown(u32);
+
+    impl Sequence for Countdown {
+        type Item = u32;
+
+        fn next_item(&mut self) -> Option<Self::Item> {
+            self.0 = self.0.checked_sub(1)?;
+            Some(self.0)
+        }
+    }
+
+    // the associated type can be constrained in bounds like a generic parameter `S: Sequence<Item = u32>`
+    fn sum<S: Sequence<Item = u32>>(mut sequence: S) -> u32 {
+        let mut sum = 0;
+        while let Some(item) = sequence.next_item() {
+            sum += item;
+        }
+        sum
+    }
+
+    assert_eq!(sum(Countdown(4)), 3 + 2 + 1);
+
+    let mut countdown = Countdown(2);
+    let item = countdown.next_item(); // no annotation needed -> `Option<u32>`
+
+    assert_eq!(item, Some(1));
+}
+
+/// Const generics are generic parameters that are values instead of types `const N: usize`.
+/// They are mostly used for the length of arrays `[T; N]`, which is part of the type.
+/// Functions and types can work with arrays of any length without losing the length information (unlike slices `&[T]`).
+#[test]
+fn const_generics() {
+    fn sum<const N: usize>(values: [i32; N]) -> i32 {
+        values.iter().sum()
+    }
+
+    assert_eq!(sum([1, 2, 3]), 6); // `N` is inferred as 3
+    assert_eq!(sum::<2>([1, 2]), 3); // or specified explicitly
+    assert_eq!(sum([]), 0); // `N` = 0
+
+    // the length is known at compile time -> the returned array has the length of the input
+    fn double<T: Copy + std::ops::Add<Output = T>, const N: usize>(values: [T; N]) -> [T; N] {
+        values.map(|x| x + x)
+    }
+
+    let doubled: [f32; 2] = double([1.0, 2.5]);
+
+    assert_eq!(doubled, [2.0, 5.0]);
+
+    // split a slice into arrays of length `N` -> the length of the chunks is part of the type
+    fn chunks<T: Copy + Default, const N: usize>(values: &[T]) -> Vec<[T; N]> {
+        values
+            .chunks(N)
+            .map(|chunk| {
+                let mut array = [T::default(); N]; // the last chunk is padded with the default value
+                array[..chunk.len()].copy_from_slice(chunk);
+                array
+            })
+            .collect()
+    }
+
+    let pairs: Vec<[u8; 2]> = chunks(&[1, 2, 3, 4, 5]);
+
+    assert_eq!(pairs, vec![[1, 2], [3, 4], [5, 0]]);
+
+    // types can have const parameters as well -> the dimensions of a matrix are checked by the compiler
+    #[derive(Debug, PartialEq)]
+    struct Matrix<const ROWS: usize, const COLS: usize>([[i32; COLS]; ROWS]);
+
+    impl<const ROWS: usize, const COLS: usize> Matrix<ROWS, COLS> {
+        fn transpose(&self) -> Matrix<COLS, ROWS> {
+            let mut transposed = [[0; ROWS]; COLS];
+            for (i, row) in self.0.iter().enumerate() {
+                for (j, value) in row.iter().enumerate() {
+                    transposed[j][i] = *value;
+                }
+            }
+            Matrix(transposed)
+        }
+    }
+
+    let matrix = Matrix([[1, 2, 3], [4, 5, 6]]); // `Matrix<2, 3>`
+
+    let transposed: Matrix<3, 2> = matrix.transpose();
+
+    assert_eq!(transposed, Matrix([[1, 4], [2, 5], [3, 6]]));
+
+    // `let wrong: Matrix<2, 3> = matrix.transpose();` -> Error: mismatched types (see the module documentation)
+}
+
+/// A blanket implementation implements a trait for every type that satisfies a bound `impl<T: Bound> Trait for T`.
+/// The standard library uses them a lot, e.g. `impl<T: Display> ToString for T` gives every `Display` type a `to_string()` method.
+/// A type can't implement the trait a second time, because it would conflict with the blanket implementation (see the module documentation).
+#[test]
+fn blanket_impls() {
+    use std::fmt::Debug;
+
+    trait Describe {
+        fn describe(&self) -> String;
+    }
+
+    // every type that implements `Debug` implements `Describe`
+    impl<T: Debug> Describe for T {
+        fn describe(&self) -> String {
+            format!("<{self:?}>")
+        }
+    }
+
+    #[derive(Debug)]
+    struct Iron;
+
+    assert_eq!(1.describe(), "<1>");
+    assert_eq!("Fe".describe(), "<\"Fe\">");
+    assert_eq!(Iron.describe(), "<Iron>");
+    assert_eq!(vec![Iron].describe(), "<[Iron]>"); // `Vec<Iron>` is `Debug` because `Iron` is `Debug`
+
+    // the `ToString` blanket implementation of the standard library
+    struct Gold;
+
+    impl std::fmt::Display for Gold {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Au")
+        }
+    }
+
+    assert_eq!(Gold.to_string(), "Au"); // `to_string()` was never implemented for `Gold`
+}
+
+/// `where` clauses can express bounds that don't fit into the angle brackets, like bounds on other types or higher-ranked trait bounds.
+/// A higher-ranked trait bound `for<'a> Fn(&'a str) -> &'a str` means "for every lifetime `'a`".
+/// It is required when the function calls the closure with references to its own local variables,
+/// because no lifetime the caller could name lives as short as those locals.
+#[test]
+fn higher_ranked_trait_bounds() {
+    fn first_word_length<F>(f: F) -> usize
+    where
+        F: for<'a> Fn(&'a str) -> &'a str, // `f` has to work for the lifetime of `text` which only exists inside this function
+    {
+        let text = String::from("hello world");
+        let word = f(&text);
+        word.len()
+    }
+
+    assert_eq!(first_word_length(|s| s.split(' ').next().unwrap()), 5);
+    assert_eq!(first_word_length(|s| s.trim()), 11);
+
+    // `Fn(&str) -> &str` is syntactic sugar for the same higher-ranked bound (lifetime elision)
+    fn apply<F: Fn(&str) -> &str>(f: F, text: &str) -> String {
+        f(text).to_string()
+    }
+
+    assert_eq!(apply(|s| &s[..2], "Fe"), "Fe");
+
+    // `where` clauses can also put bounds on types other than the generic parameters
+    fn join<T>(values: &[T]) -> String
+    where
+        T: ToString,
+        [T]: std::fmt::Debug, // bound on the slice type
+    {
+        values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    assert_eq!(join(&[1, 2, 3]), "1, 2, 3");
+}
+
+/// `PhantomData<T>` is a zero-sized marker that makes a type "use" a generic parameter without storing a value of it.
+/// This allows encoding states in the type system (typestate): methods that are only valid in a certain state only exist for that state.
+/// Using a builder in the wrong state is a compile error instead of a runtime error (see the module documentation).
+#[test]
+fn phantom_data_typestate() {
+    use std::marker::PhantomData;
+
+    assert_eq!(std::mem::size_of::<PhantomData<String>>(), 0); // no runtime cost
+
+    // states of the builder -> types without values
+    struct NoUrl;
+    struct HasUrl;
+
+    struct RequestBuilder<State> {
+        url: String,
+        headers: Vec<(String, String)>,
+        _state: PhantomData<State>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Request {
+        url: String,
+        headers: Vec<(String, String)>,
+    }
+
+    impl RequestBuilder<NoUrl> {
+        fn new() -> Self {
+            RequestBuilder {
+                url: String::new(),
+                headers: Vec::new(),
+                _state: PhantomData,
+            }
+        }
+
+        // setting the url changes the state of the builder
+        fn url(self, url: &str) -> RequestBuilder<HasUrl> {
+            RequestBuilder {
+                url: url.to_string(),
+                headers: self.headers,
+                _state: PhantomData,
+            }
+        }
+    }
+
+    // available in every state
+    impl<State> RequestBuilder<State> {
+        fn header(mut self, key: &str, value: &str) -> Self {
+            self.headers.push((key.to_string(), value.to_string()));
+            self
+        }
+    }
+
+    // only available when the url is set
+    impl RequestBuilder<HasUrl> {
+        fn build(self) -> Request {
+            Request {
+                url: self.url,
+                headers: self.headers,
+            }
+        }
+    }
+
+    let request = RequestBuilder::new()
+        .header("Accept", "text/html")
+        .url("https://en.wikipedia.org/")
+        .build();
+
+    assert_eq!(request.url, "https://en.wikipedia.org/");
+    assert_eq!(request.headers.len(), 1);
+
+    // `RequestBuilder::new().build();` -> Error: no method named `build` found for `RequestBuilder<NoUrl>`
+
+    // units of measurement -> values with different units can't be mixed up
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    struct Length<Unit>(f64, PhantomData<Unit>);
+
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    struct Meters;
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    struct Feet;
+
+    impl<Unit> std::ops::Add for Length<Unit> {
+        type Output = Self;
+
+        fn add(self, other: Self) -> Self {
+            Length(self.0 + other.0, PhantomData)
+        }
+    }
+
+    let a: Length<Meters> = Length(1.0, PhantomData);
+    let b: Length<Meters> = Length(2.0, PhantomData);
+    let _c: Length<Feet> = Length(3.0, PhantomData);
+
+    assert_eq!((a + b).0, 3.0);
+
+    // `a + _c` -> Error: mismatched types, expected `Length<Meters>`, found `Length<Feet>`
+}
+
+/// https://doc.rust-lang.org/reference/items/implementations.html#orphan-rules
+///
+/// A trait can only be implemented for a type if the trait or the type is defined in the current crate.
+/// This prevents two crates from implementing the same trait for the same type in conflicting ways.
+/// Implementing a foreign trait for a foreign type fails (see the module documentation), the workaround is the newtype pattern:
+/// wrap the foreign type in a local type and implement the trait for the wrapper.
+#[test]
+fn orphan_rule() {
+    use std::fmt::Display;
+
+    // local trait for a foreign type -> allowed
+    trait Double {
+        fn double(&self) -> Self;
+    }
+
+    impl Double for i32 {
+        fn double(&self) -> Self {
+            self * 2
+        }
+    }
+
+    assert_eq!(4.double(), 8);
+
+    // foreign trait for a local type -> allowed
+    struct Element(&'static str);
+
+    impl Display for Element {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Element {}", self.0)
+        }
+    }
+
+    assert_eq!(Element("Fe").to_string(), "Element Fe");
+
+    // foreign trait for a foreign type -> `impl Display for Vec<String>` is not allowed -> newtype
+    struct Elements(Vec<String>);
+
+    impl Display for Elements {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "[{}]", self.0.join(", "))
+        }
+    }
+
+    let elements = Elements(vec!["Fe".to_string(), "Cu".to_string()]);
+
+    assert_eq!(elements.to_string(), "[Fe, Cu]");
+}
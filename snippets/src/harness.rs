@@ -0,0 +1,69 @@
+//! Runs a lesson in Rust and in another language and compares what both did.
+//!
+//! - `python` (feature `python`): a Python snippet in an embedded interpreter and a Rust closure,
+//!   compared by their output and their result or error
+//! - `c` (feature `cc`): a C file from `c/src` or a C string, compiled with the host's C compiler and run with a timeout,
+//!   its output, exit code or signal checked next to the Rust version
+//!
+//! A mismatch panics with both sides and a line diff of their output, e.g.:
+//!
+//! ```text
+//! Python and Rust differ:
+//!   Python: failed: Exception: file not found
+//!   Rust:   returned "content2"
+//!   output (- Python, + Rust):
+//!     opening file2
+//!   - file not found
+//!   + found file2
+//! ```
+
+#[cfg(feature = "cc")]
+pub(crate) mod c;
+#[cfg(feature = "python")]
+pub(crate) mod python;
+
+/// The lines of both texts, unchanged lines indented, lines only in `left` marked with `-`, only in `right` with `+`.
+#[allow(dead_code)] // <- only used by the harnesses of enabled features
+pub(crate) fn diff(left: &str, right: &str) -> String {
+    let left: Vec<&str> = left.lines().collect();
+    let right: Vec<&str> = right.lines().collect();
+
+    // longest common subsequence: `common[i][j]` for `left[i..]` and `right[j..]`
+    let mut common = vec![vec![0; right.len() + 1]; left.len() + 1];
+
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            common[i][j] = if left[i] == right[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            lines.push(format!("  {}", left[i]));
+            i += 1;
+            j += 1;
+        } else if j == right.len() || (i < left.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", left[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", right[j]));
+            j += 1;
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[test]
+fn line_diff() {
+    assert_eq!(diff("a\nb\nc", "a\nc\nd"), "  a\n- b\n  c\n+ d");
+    assert_eq!(diff("", "a"), "+ a");
+    assert_eq!(diff("same", "same"), "  same");
+}
@@ -0,0 +1,316 @@
+//! Compiles C and C++ programs with the host's compiler (found by the `cc` crate, like build.rs does), runs them and captures what they did.
+//!
+//! ```ignore
+//! let program = Program::file("uninitialized.c") // <- c/src/uninitialized.c
+//!     .documented_in(include_str!("safety.rs")) // <- the copy in the `/* uninitialized.c .. */` comment has to be the same
+//!     .compile()
+//!     .unwrap();
+//!
+//! let output = program.run();
+//!
+//! assert_eq!(output.code, Some(0));
+//! ```
+//!
+//! Programs with undefined behaviour can hang or crash, so they run with a timeout and a signal (e.g. `SIGSEGV`) is captured instead of failing the test.
+//! Undefined behaviour that doesn't crash (reading freed memory) is made visible with `.address_sanitizer()`, which reports it on stderr.
+//!
+//! Files ending in `.cpp` are C++ (C++17) from `cpp/src`, the others C from `c/src`.
+
+use std::fmt::{self, Display};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::raii::TempDir;
+
+/// A C or C++ program, from a file in `c/src` or `cpp/src` or from a string.
+pub(crate) struct Program {
+    name: String,
+    source: String,
+    timeout: Duration,
+    address_sanitizer: bool,
+}
+
+impl Program {
+    /// `c/src/<name>` or `cpp/src/<name>`
+    pub(crate) fn file(name: &str) -> Self {
+        let directory = if is_cpp(name) {
+            "../cpp/src"
+        } else {
+            "../c/src"
+        };
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(directory)
+            .join(name);
+        let source = std::fs::read_to_string(&path)
+            .unwrap_or_else(|error| panic!("{} could not be read: {error}", path.display()));
+
+        Self::inline(name, &source)
+    }
+
+    pub(crate) fn inline(name: &str, source: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            timeout: Duration::from_secs(10),
+            address_sanitizer: false,
+        }
+    }
+
+    pub(crate) fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Compiles with AddressSanitizer, the program aborts with a report on stderr (`heap-use-after-free`, ..)
+    /// at the first invalid memory access instead of reading whatever is there.
+    pub(crate) fn address_sanitizer(mut self) -> Self {
+        self.address_sanitizer = true;
+        self
+    }
+
+    /// Asserts that the lesson shows the same code in a `/* <name> .. */` comment as the program runs.
+    /// The comment is indented like the Rust code around it, the indentation is ignored.
+    #[track_caller]
+    pub(crate) fn documented_in(self, rust_source: &str) -> Self {
+        let comment = comment(rust_source, &self.name)
+            .unwrap_or_else(|| panic!("there is no `/* {}` comment", self.name));
+
+        assert!(
+            comment == self.source.trim(),
+            "the comment `/* {}` differs from the file (- comment, + file):\n{}",
+            self.name,
+            super::diff(&comment, self.source.trim())
+        );
+
+        self
+    }
+
+    /// Compiles the program into an executable in its own temporary directory, `Err` has the errors of the compiler.
+    /// The directory is removed when the `Executable` is dropped.
+    pub(crate) fn compile(&self) -> Result<Executable, String> {
+        let directory = TempDir::new("c").map_err(|error| error.to_string())?;
+
+        let source = directory.path().join(&self.name);
+        // the whole name, `harness.c` and `harness.cpp` mustn't build the same `harness`
+        let path = directory.path().join(format!(
+            "{}.out{}",
+            self.name,
+            std::env::consts::EXE_SUFFIX
+        ));
+
+        std::fs::write(&source, &self.source).map_err(|error| error.to_string())?;
+
+        // the same compiler and flags build.rs would use, `TARGET` is passed on by build.rs
+        let compiler = cc::Build::new()
+            .target(env!("TARGET"))
+            .host(env!("TARGET"))
+            .opt_level(0)
+            .cpp(is_cpp(&self.name))
+            .cargo_metadata(false)
+            .try_get_compiler()
+            .map_err(|error| error.to_string())?;
+
+        let mut command = compiler.to_command();
+        let msvc = compiler.is_like_msvc();
+
+        if is_cpp(&self.name) {
+            command.arg(if msvc { "/std:c++17" } else { "-std=c++17" });
+        }
+
+        if self.address_sanitizer {
+            command.arg(if msvc {
+                "/fsanitize=address"
+            } else {
+                "-fsanitize=address"
+            });
+        }
+
+        if msvc {
+            command.arg(&source).arg(format!("/Fe{}", path.display()));
+        } else {
+            command.arg(&source).arg("-o").arg(&path);
+        }
+
+        let output = command.output().map_err(|error| error.to_string())?;
+        let messages = String::from_utf8_lossy(&output.stderr).into_owned();
+
+        if output.status.success() {
+            Ok(Executable {
+                _directory: directory,
+                path,
+                warnings: messages,
+                timeout: self.timeout,
+            })
+        } else {
+            Err(messages)
+        }
+    }
+}
+
+/// A compiled program, `warnings` has everything the compiler reported.
+pub(crate) struct Executable {
+    _directory: TempDir, // <- owns the source and the executable, dropped (and removed) with the `Executable`
+    path: PathBuf,
+    pub(crate) warnings: String,
+    timeout: Duration,
+}
+
+/// What a program did.
+#[derive(Debug, Default)]
+pub(crate) struct Output {
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    /// `None` if the program was killed by a signal or the timeout.
+    pub(crate) code: Option<i32>,
+    /// The signal that killed the program (only on unix), e.g. `11` (`SIGSEGV`).
+    pub(crate) signal: Option<i32>,
+    pub(crate) timed_out: bool,
+}
+
+impl Executable {
+    /// Runs the program until it exits or the timeout kills it (see `process::wait_timeout`).
+    pub(crate) fn run(&self) -> Output {
+        let mut child = Command::new(&self.path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|error| {
+                panic!("{} could not be started: {error}", self.path.display())
+            });
+
+        // read on threads, a program that fills a pipe would otherwise block until the timeout
+        let read = |mut pipe: Box<dyn Read + Send>| {
+            std::thread::spawn(move || {
+                let mut bytes = Vec::new();
+                let _ = pipe.read_to_end(&mut bytes);
+                String::from_utf8_lossy(&bytes).into_owned()
+            })
+        };
+
+        let stdout = read(Box::new(child.stdout.take().unwrap()));
+        let stderr = read(Box::new(child.stderr.take().unwrap()));
+
+        let status = crate::process::wait_timeout(&mut child, self.timeout).unwrap();
+
+        let mut output = Output {
+            stdout: stdout.join().unwrap(),
+            stderr: stderr.join().unwrap(),
+            code: status.and_then(|status| status.code()),
+            timed_out: status.is_none(),
+            ..Output::default()
+        };
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+
+            output.signal = status.and_then(|status| status.signal());
+        }
+
+        output
+    }
+}
+
+impl Output {
+    /// Asserts that the program printed the same as the Rust version, with a diff if it didn't.
+    #[track_caller]
+    pub(crate) fn assert_stdout(&self, rust: &str) {
+        assert!(
+            self.stdout == rust,
+            "C and Rust printed different output (- C, + Rust):\n{}\n{self}",
+            super::diff(&self.stdout, rust)
+        );
+    }
+}
+
+impl Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.code, self.signal) {
+            _ if self.timed_out => writeln!(f, "killed after the timeout")?,
+            (Some(code), _) => writeln!(f, "exit code {code}")?,
+            (None, Some(signal)) => writeln!(f, "killed by signal {signal}")?,
+            (None, None) => writeln!(f, "killed")?,
+        }
+
+        writeln!(f, "stdout: {:?}", self.stdout)?;
+        write!(f, "stderr: {:?}", self.stderr)
+    }
+}
+
+fn is_cpp(name: &str) -> bool {
+    name.ends_with(".cpp")
+}
+
+/// The content of the block comment that starts with `/* <name>`, without the indentation of the Rust code.
+fn comment(rust_source: &str, name: &str) -> Option<String> {
+    let start = rust_source.find(&format!("/* {name}\n"))? + name.len() + 4;
+    let end = start + rust_source[start..].find("*/")?;
+
+    let lines: Vec<&str> = rust_source[start..end].lines().collect();
+
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indentation..).unwrap_or("").trim_end())
+        .collect();
+
+    Some(lines.join("\n").trim().to_string())
+}
+
+#[test]
+fn harness() {
+    let program = Program::inline(
+        "harness.c",
+        "#include <stdio.h>\nint main() { printf(\"Fe\\n\"); fprintf(stderr, \"Cu\"); return 3; }",
+    )
+    .compile()
+    .unwrap();
+
+    let output = program.run();
+
+    assert_eq!(
+        (output.stdout.as_str(), output.stderr.as_str()),
+        ("Fe\n", "Cu")
+    );
+    assert_eq!(output.code, Some(3));
+    output.assert_stdout("Fe\n");
+
+    let hangs = Program::inline("hangs.c", "int main() { while (1) {} }")
+        .timeout(Duration::from_millis(200))
+        .compile()
+        .unwrap()
+        .run();
+
+    assert!(hangs.timed_out);
+    assert_eq!(hangs.code, None);
+
+    let errors = Program::inline("errors.c", "int main() { return undefined; }").compile();
+
+    assert!(errors.err().unwrap().contains("undefined"));
+
+    let cpp = Program::inline(
+        "harness.cpp",
+        "#include <iostream>\n#include <string_view>\nint main() { std::string_view sv = \"Fe\"; std::cout << sv; }",
+    )
+    .compile()
+    .unwrap()
+    .run();
+
+    cpp.assert_stdout("Fe");
+
+    let source =
+        "fn main() {\n    /* example.c\n\n    int main() {\n        return 0;\n    }\n\n    */\n}";
+
+    assert_eq!(
+        comment(source, "example.c").unwrap(),
+        "int main() {\n    return 0;\n}"
+    );
+}
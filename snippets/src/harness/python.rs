@@ -0,0 +1,336 @@
+//! https://pyo3.rs/v0.20.0/python_from_rust
+//!
+//! `run_python` runs a snippet in the embedded interpreter, `run_rust` runs a closure, `compare` checks them against each other:
+//!
+//! ```ignore
+//! compare(
+//!     run_python("print('H2O')\nresult = 15.999 + 2 * 1.008"),
+//!     run_rust(|stdout| {
+//!         writeln!(stdout, "H2O")?;
+//!         Ok::<_, std::fmt::Error>(15.999 + 2.0 * 1.008)
+//!     }),
+//! )
+//! .assert_equivalent();
+//! ```
+//!
+//! Python's result is the variable `result` (`None` if the snippet doesn't set it), an exception is a failure.
+//! Rust's result is the `Ok` of the closure, an `Err` is a failure.
+//! Both are converted to `Value`s so that e.g. a Python `list` can be compared with a Rust `Vec`.
+
+use std::fmt::{self, Display};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple};
+
+/// A value in a form both languages can be converted into.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    List(Vec<Value>),
+    Dict(Vec<(Value, Value)>),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |values: Vec<String>| values.join(", ");
+
+        match self {
+            Value::None => write!(f, "None"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value:?}"),
+            Value::Str(value) => write!(f, "{value:?}"),
+            Value::List(values) => write!(
+                f,
+                "[{}]",
+                join(values.iter().map(Value::to_string).collect())
+            ),
+            Value::Dict(entries) => write!(
+                f,
+                "{{{}}}",
+                join(
+                    entries
+                        .iter()
+                        .map(|(key, value)| format!("{key}: {value}"))
+                        .collect()
+                )
+            ),
+        }
+    }
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Outcome {
+    Returned(Value),
+    /// The exception (`ZeroDivisionError: division by zero`) or the `Err` (its `Display`).
+    Failed(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Returned(value) => write!(f, "returned {value}"),
+            Outcome::Failed(error) => write!(f, "failed: {error}"),
+        }
+    }
+}
+
+/// What a snippet printed and how it ended.
+#[derive(Debug)]
+pub(crate) struct Run {
+    pub(crate) stdout: String,
+    pub(crate) outcome: Outcome,
+}
+
+/// Conversion of Rust results into `Value`s.
+pub(crate) trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+macro_rules! into_value {
+    ($variant:ident: $($ty:ty),*) => {
+        $(impl IntoValue for $ty {
+            fn into_value(self) -> Value {
+                Value::$variant(self.into())
+            }
+        })*
+    };
+}
+
+into_value!(Bool: bool);
+into_value!(Int: i8, i16, i32, i64, u8, u16, u32);
+into_value!(Float: f32, f64);
+into_value!(Str: String, &str);
+
+impl IntoValue for () {
+    fn into_value(self) -> Value {
+        Value::None
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Value {
+        self.map_or(Value::None, IntoValue::into_value)
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        Value::List(self.into_iter().map(IntoValue::into_value).collect())
+    }
+}
+
+/// Converts a Python object, objects with `tolist()` (numpy arrays) are converted as lists, unknown objects as their `repr`.
+fn to_value(object: &PyAny) -> PyResult<Value> {
+    let value = if object.is_none() {
+        Value::None
+    } else if let Ok(value) = object.downcast::<PyBool>() {
+        Value::Bool(value.is_true()) // <- before `int`, `bool` is a subclass of `int` in Python
+    } else if object.is_instance_of::<PyLong>() {
+        Value::Int(object.extract()?)
+    } else if object.is_instance_of::<PyFloat>() {
+        Value::Float(object.extract()?)
+    } else if let Ok(value) = object.downcast::<PyString>() {
+        Value::Str(value.to_str()?.to_string())
+    } else if let Ok(list) = object.downcast::<PyList>() {
+        Value::List(list.iter().map(to_value).collect::<PyResult<_>>()?)
+    } else if let Ok(tuple) = object.downcast::<PyTuple>() {
+        Value::List(tuple.iter().map(to_value).collect::<PyResult<_>>()?)
+    } else if let Ok(dict) = object.downcast::<PyDict>() {
+        let entries = dict
+            .iter()
+            .map(|(key, value)| Ok((to_value(key)?, to_value(value)?)));
+        Value::Dict(entries.collect::<PyResult<_>>()?)
+    } else if object.hasattr("tolist")? {
+        to_value(object.call_method0("tolist")?)?
+    } else {
+        Value::Str(object.repr()?.to_string())
+    };
+
+    Ok(value)
+}
+
+/// Runs a Python snippet, see `run_python_with`.
+pub(crate) fn run_python(code: &str) -> Run {
+    run_python_with(code, |_, _| Ok(()))
+}
+
+/// Runs a Python snippet after `setup` defined its inputs as variables (`variables.set_item("name", value)`).
+/// The output of `print` is captured, an exception ends the run as a failure.
+pub(crate) fn run_python_with(
+    code: &str,
+    setup: impl FnOnce(Python<'_>, &PyDict) -> PyResult<()>,
+) -> Run {
+    pyo3::prepare_freethreaded_python(); // <- starts the interpreter once, later calls do nothing
+
+    let run = Python::with_gil(|py| -> PyResult<Run> {
+        let variables = PyDict::new(py); // <- globals and locals of the snippet, fresh for every run
+
+        setup(py, variables)?;
+
+        // `print` writes to `sys.stdout`, which is replaced by a buffer while the snippet runs
+        let sys = py.import("sys")?;
+        let stdout = sys.getattr("stdout")?;
+        let buffer = py.import("io")?.getattr("StringIO")?.call0()?;
+
+        sys.setattr("stdout", buffer)?;
+        let result = py.run(code, Some(variables), Some(variables));
+        sys.setattr("stdout", stdout)?;
+
+        let outcome = match result {
+            Ok(()) => Outcome::Returned(match variables.get_item("result")? {
+                Some(result) => to_value(result)?,
+                None => Value::None,
+            }),
+            Err(error) => Outcome::Failed(format!(
+                "{}: {}",
+                error.get_type(py).name()?,
+                error.value(py)
+            )),
+        };
+
+        Ok(Run {
+            stdout: buffer.call_method0("getvalue")?.extract()?,
+            outcome,
+        })
+    });
+
+    run.expect("the harness could not run the Python snippet")
+}
+
+/// Runs the Rust side, the closure writes its output into `stdout` (with `write!`/`writeln!`) instead of printing it.
+pub(crate) fn run_rust<T: IntoValue, E: Display>(
+    function: impl FnOnce(&mut String) -> Result<T, E>,
+) -> Run {
+    let mut stdout = String::new();
+
+    let outcome = match function(&mut stdout) {
+        Ok(value) => Outcome::Returned(value.into_value()),
+        Err(error) => Outcome::Failed(error.to_string()),
+    };
+
+    Run { stdout, outcome }
+}
+
+/// What one side is expected to do when the languages diverge.
+#[derive(Debug)]
+pub(crate) enum Expected {
+    Returns(Value),
+    /// A failure whose message contains the text.
+    Fails(&'static str),
+}
+
+impl Expected {
+    fn matches(&self, outcome: &Outcome) -> bool {
+        match (self, outcome) {
+            (Expected::Returns(expected), Outcome::Returned(value)) => expected == value,
+            (Expected::Fails(expected), Outcome::Failed(error)) => error.contains(expected),
+            _ => false,
+        }
+    }
+}
+
+pub(crate) struct Comparison {
+    python: Run,
+    rust: Run,
+}
+
+pub(crate) fn compare(python: Run, rust: Run) -> Comparison {
+    Comparison { python, rust }
+}
+
+impl Comparison {
+    /// Both printed the same and returned the same value, or both failed (the messages are not compared,
+    /// a Python exception and a Rust error are rarely worded the same).
+    #[track_caller]
+    pub(crate) fn assert_equivalent(&self) {
+        let outcomes = match (&self.python.outcome, &self.rust.outcome) {
+            (Outcome::Returned(python), Outcome::Returned(rust)) => python == rust,
+            (Outcome::Failed(_), Outcome::Failed(_)) => true,
+            _ => false,
+        };
+
+        assert!(
+            outcomes && self.python.stdout == self.rust.stdout,
+            "Python and Rust differ:\n{self}"
+        );
+    }
+
+    /// The languages behave differently in a known way, e.g. Python raises where Rust returns an `Err`.
+    #[track_caller]
+    pub(crate) fn assert_diverges(&self, python: Expected, rust: Expected) {
+        assert!(
+            python.matches(&self.python.outcome) && rust.matches(&self.rust.outcome),
+            "expected Python to end with {python:?} and Rust with {rust:?}:\n{self}"
+        );
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Python: {}", self.python.outcome)?;
+        writeln!(f, "  Rust:   {}", self.rust.outcome)?;
+
+        if self.python.stdout != self.rust.stdout {
+            writeln!(f, "  output (- Python, + Rust):")?;
+
+            for line in super::diff(&self.python.stdout, &self.rust.stdout).lines() {
+                writeln!(f, "  {line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn harness() {
+    use std::fmt::Write;
+
+    let python = run_python("print('H2O')\nresult = [2, 16, {'O': True}]");
+
+    assert_eq!(python.stdout, "H2O\n");
+    assert_eq!(
+        python.outcome.to_string(),
+        r#"returned [2, 16, {"O": true}]"#
+    );
+
+    let python = run_python("print('H2O')\nresult = 15.999 + 2 * 1.008");
+
+    let rust = run_rust(|stdout| {
+        writeln!(stdout, "H2O")?;
+        Ok::<_, fmt::Error>(15.999 + 2.0 * 1.008)
+    });
+
+    compare(python, rust).assert_equivalent();
+
+    let python = run_python_with("result = 1 / divisor", |_, variables| {
+        variables.set_item("divisor", 0)
+    });
+
+    let rust = run_rust(|_| 1u8.checked_div(0).ok_or("division by zero"));
+
+    compare(python, rust).assert_equivalent(); // <- both fail
+
+    let python = run_python("result = 255 + 1"); // <- Python integers have no maximum
+    let rust = run_rust(|_| 255u8.checked_add(1).ok_or("overflow"));
+
+    let comparison = compare(python, rust);
+
+    comparison.assert_diverges(
+        Expected::Returns(Value::Int(256)),
+        Expected::Fails("overflow"),
+    );
+
+    let mismatch = std::panic::catch_unwind(|| comparison.assert_equivalent()).unwrap_err();
+
+    assert_eq!(
+        mismatch.downcast_ref::<String>().unwrap(),
+        "Python and Rust differ:\n  Python: returned 256\n  Rust:   failed: overflow\n"
+    );
+}
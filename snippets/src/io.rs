@@ -0,0 +1,420 @@
+//! https://doc.rust-lang.org/std/io/index.html
+//! https://doc.rust-lang.org/std/fs/index.html
+//!
+//! Input and output is built on a few traits instead of concrete types:
+//!
+//! - `Read`: reads bytes into a buffer (`File`, `TcpStream`, `&[u8]`, `Stdin`, ..)
+//! - `Write`: writes bytes (`File`, `Vec<u8>`, `Stdout`, ..), `write!` and `writeln!` work with every `Write`
+//! - `BufRead`: a `Read` with an internal buffer, which allows reading lines (`BufReader<File>`, `&[u8]`, `Cursor`, ..)
+//! - `Seek`: moves the position in a stream (`File`, `Cursor`)
+//!
+//! Code that takes `impl Read` or `impl BufRead` instead of a `File` can be tested in memory with a `Cursor` or a byte slice.
+//! Every operation returns `io::Result<T>`, so errors are propagated with `?`.
+//!
+//! The methods of a trait can only be called if the trait is in scope:
+//!
+//! ```compile_fail,E0599
+//! let mut reader = std::io::BufReader::new("iron\ngold\n".as_bytes());
+//! let mut line = String::new();
+//!
+//! reader.read_line(&mut line).unwrap(); // <- no method named `read_line` found, `use std::io::BufRead;` is missing
+//! ```
+//!
+//! The tests only touch files inside their own temporary directory (`raii::TempDir`), which is removed when the test ends.
+
+use std::fs::File;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts the lines that are not empty. Works with any `BufRead`: a `BufReader<File>`, a `Cursor` or a byte slice.
+///
+/// ```
+/// use rust_snippets::io::count_lines;
+///
+/// assert_eq!(count_lines("iron\n\ngold\n".as_bytes()).unwrap(), 2);
+/// assert_eq!(count_lines(std::io::Cursor::new("silver")).unwrap(), 1); // <- the last line doesn't need a `\n`
+/// ```
+pub fn count_lines(reader: impl BufRead) -> std::io::Result<usize> {
+    let mut count = 0;
+
+    for line in reader.lines() {
+        if !line?.trim().is_empty() {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it to `path`.
+/// A rename within a directory is atomic: readers see the old or the new file, never a half written one,
+/// and if writing fails (disk full, crash) the old file is unchanged.
+/// Every call writes its own temporary file, so concurrent writers don't mix their contents: the last rename wins.
+/// On Unix the directory is synced after the rename, so the new file is still there after a power failure.
+///
+/// ```
+/// use rust_snippets::io::write_atomically;
+/// use rust_snippets::raii::TempDir;
+///
+/// let directory = TempDir::new("atomic").unwrap();
+/// let path = directory.path().join("elements.txt");
+///
+/// write_atomically(&path, "iron").unwrap();
+/// write_atomically(&path, "gold").unwrap();
+///
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "gold");
+/// assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1); // <- no temporary file left behind
+/// ```
+pub fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "the path has no file name",
+        )
+    })?;
+
+    // in the same directory: a rename across file systems isn't atomic (and may not work at all),
+    // with the process id and a counter (like `raii::TempFile`) every writer has its own temporary file
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let temporary = path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| {
+        // `create_new` fails instead of truncating a file that is already there (left behind by a crash, or someone else's)
+        let mut file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(&temporary)?;
+
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?; // <- the contents are on the disk before the rename makes them visible
+
+        std::fs::rename(&temporary, path)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+
+    result?;
+
+    // the rename is a change of the directory, which is only durable once the directory is synced too
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+
+        File::open(parent)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// `Read` and `Write` work on bytes. `&[u8]` is a reader and `Vec<u8>` a writer, so they need no file.
+#[test]
+fn read_and_write() {
+    use std::io::Read;
+
+    let mut output = Vec::new();
+
+    output.write_all(b"Fe ").unwrap();
+    write!(output, "{} ", 26).unwrap(); // <- `write!` formats into any `Write`
+    writeln!(output, "iron").unwrap();
+
+    assert_eq!(output, b"Fe 26 iron\n");
+
+    let mut input: &[u8] = &output; // <- reading advances the slice
+    let mut symbol = [0; 2];
+
+    input.read_exact(&mut symbol).unwrap();
+
+    assert_eq!(&symbol, b"Fe");
+
+    let mut rest = String::new();
+
+    input.read_to_string(&mut rest).unwrap(); // <- fails with `InvalidData` if the bytes aren't UTF-8
+
+    assert_eq!(rest, " 26 iron\n");
+
+    // `read` fills as much of the buffer as it can, 0 means the end of the input
+    assert_eq!(input.read(&mut symbol).unwrap(), 0);
+}
+
+/// `BufReader` reads a file in larger blocks and splits it into lines. `lines()` yields `io::Result<String>`,
+/// so an error while reading (not only while opening) is propagated with `?`, here together with parse errors in a `Box<dyn Error>`.
+#[test]
+fn read_lines() {
+    use crate::raii::TempDir;
+
+    fn total_weight(path: &Path) -> Result<f32, Box<dyn std::error::Error>> {
+        let reader = std::io::BufReader::new(File::open(path)?);
+        let mut total = 0.0;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+
+            let Some((_, weight)) = line.split_once(':') else {
+                return Err(format!("line {}: expected `element: weight`", index + 1).into());
+            };
+
+            total += weight.trim().parse::<f32>()?;
+        }
+
+        Ok(total)
+    }
+
+    let directory = TempDir::new("read_lines").unwrap();
+    let path = directory.path().join("weights.txt");
+
+    assert!(total_weight(&path).is_err()); // <- the file doesn't exist
+
+    std::fs::write(&path, "H: 1.008\nO: 15.999\nO: 15.999\n").unwrap();
+
+    assert_eq!(total_weight(&path).unwrap(), 1.008 + 15.999 + 15.999);
+
+    std::fs::write(&path, "H: 1.008\nwater\n").unwrap();
+
+    assert_eq!(
+        total_weight(&path).unwrap_err().to_string(),
+        "line 2: expected `element: weight`"
+    );
+
+    std::fs::write(&path, "H: light\n").unwrap();
+
+    assert_eq!(
+        total_weight(&path).unwrap_err().to_string(),
+        "invalid float literal"
+    );
+}
+
+/// `Seek` moves the position of the next read or write: from the start, from the end or relative to the current position.
+#[test]
+fn seek() {
+    use crate::raii::TempDir;
+    use std::io::{Read, Seek, SeekFrom};
+
+    let directory = TempDir::new("seek").unwrap();
+
+    let mut file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(directory.path().join("elements.txt"))
+        .unwrap();
+
+    file.write_all(b"H He Li Be").unwrap(); // <- the position is at the end now
+
+    let mut buffer = String::new();
+
+    file.rewind().unwrap(); // <- `seek(SeekFrom::Start(0))`
+    file.read_to_string(&mut buffer).unwrap();
+
+    assert_eq!(buffer, "H He Li Be");
+
+    assert_eq!(file.seek(SeekFrom::End(-2)).unwrap(), 8); // <- returns the new position from the start
+
+    file.write_all(b"N").unwrap(); // <- overwrites the "B" of "Be"
+
+    file.seek(SeekFrom::Current(-4)).unwrap();
+    buffer.clear();
+    file.read_to_string(&mut buffer).unwrap();
+
+    assert_eq!(buffer, "Li Ne");
+}
+
+/// `Cursor` wraps a `Vec<u8>` (or a slice or `String`) and implements `Read`, `BufRead`, `Write` and `Seek`.
+/// A function that is generic over the traits can be tested in memory without files.
+#[test]
+fn cursor() {
+    use std::io::{Cursor, Read, Seek};
+
+    fn uppercase(input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+        for line in input.lines() {
+            writeln!(output, "{}", line?.to_uppercase())?;
+        }
+
+        Ok(())
+    }
+
+    let mut output = Cursor::new(Vec::new());
+
+    uppercase(Cursor::new("fe\nau\n"), &mut output).unwrap(); // <- `&mut W` is a `Write` too
+
+    assert_eq!(output.get_ref(), b"FE\nAU\n");
+
+    output.rewind().unwrap(); // <- read back what was written
+
+    let mut text = String::new();
+    output.read_to_string(&mut text).unwrap();
+
+    assert_eq!(text, "FE\nAU\n");
+
+    // the same function with real files
+    let directory = crate::raii::TempDir::new("cursor").unwrap();
+    let path = directory.path().join("upper.txt");
+
+    uppercase("ag\n".as_bytes(), File::create(&path).unwrap()).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "AG\n");
+}
+
+/// `fs::metadata` returns the size, type, permissions and times of a file or directory.
+/// `read_dir` lists a directory (in no particular order), walking a tree is a recursion over it.
+#[test]
+fn metadata_and_directories() {
+    use std::path::PathBuf;
+
+    fn walk(directory: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(directory)? {
+            let entry = entry?;
+
+            if entry.file_type()?.is_dir() {
+                walk(&entry.path(), files)?;
+            } else {
+                files.push(entry.path());
+            }
+        }
+
+        Ok(())
+    }
+
+    let directory = crate::raii::TempDir::new("walk").unwrap();
+    let root = directory.path();
+
+    std::fs::create_dir_all(root.join("metals/precious")).unwrap(); // <- creates the parents too
+    std::fs::create_dir(root.join("gases")).unwrap();
+    std::fs::write(root.join("metals/iron.txt"), "Fe").unwrap();
+    std::fs::write(root.join("metals/precious/gold.txt"), "Au").unwrap();
+    std::fs::write(root.join("gases/neon.txt"), "Ne").unwrap();
+
+    let metadata = std::fs::metadata(root.join("metals/iron.txt")).unwrap();
+
+    assert!(metadata.is_file());
+    assert_eq!(metadata.len(), 2); // <- in bytes
+    assert!(std::fs::metadata(root.join("metals")).unwrap().is_dir());
+    assert!(std::fs::metadata(root.join("missing")).is_err());
+
+    let mut files = Vec::new();
+
+    walk(root, &mut files).unwrap();
+
+    let mut relative: Vec<String> = files
+        .iter()
+        .map(|path| {
+            let path = path.strip_prefix(root).unwrap();
+            let components: Vec<_> = path.iter().map(|part| part.to_string_lossy()).collect();
+
+            components.join("/") // <- the same on every platform
+        })
+        .collect();
+
+    relative.sort(); // <- `read_dir` has no order
+
+    assert_eq!(
+        relative,
+        [
+            "gases/neon.txt",
+            "metals/iron.txt",
+            "metals/precious/gold.txt"
+        ]
+    );
+}
+
+/// A crash in the middle of `fs::write` leaves a half written file, `write_atomically` leaves the old or the new one.
+#[test]
+fn atomic_write() {
+    let directory = crate::raii::TempDir::new("atomic_write").unwrap();
+    let path = directory.path().join("config.txt");
+
+    write_atomically(&path, "iron").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "iron");
+
+    write_atomically(&path, "gold").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "gold");
+
+    // writers in parallel: every one has its own temporary file, the file is one of the contents, never a mix
+    let contents: Vec<String> = ["silver", "copper", "platinum", "zinc"]
+        .iter()
+        .map(|element| element.repeat(10_000))
+        .collect();
+
+    std::thread::scope(|scope| {
+        for content in &contents {
+            scope.spawn(|| write_atomically(&path, content).unwrap());
+        }
+    });
+
+    assert!(contents.contains(&std::fs::read_to_string(&path).unwrap()));
+
+    // a failed write (the directory doesn't exist) doesn't touch anything
+    let missing = directory.path().join("missing").join("config.txt");
+
+    assert_eq!(
+        write_atomically(&missing, "silver").unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+
+    let names: Vec<_> = std::fs::read_dir(directory.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+
+    assert_eq!(names, ["config.txt"]);
+}
+
+/// Python's `with open(..) as file:` closes the file at the end of the block, also when an exception leaves it.
+/// A Rust `File` is closed when it's dropped, at the end of its scope or when `?` returns early: no `with` (or `close`) needed.
+/// Using a closed file is an exception in Python (`ValueError: I/O operation on closed file`), in Rust the dropped `file` doesn't exist anymore.
+#[cfg(feature = "python")]
+#[test]
+fn with_open_vs_python() {
+    use crate::harness::python::{compare, run_python_with, run_rust};
+    use std::fmt::Write as _;
+
+    let directory = crate::raii::TempDir::new("with_open").unwrap();
+    let path = directory.path().join("elements.txt");
+    let path = path.to_str().unwrap();
+
+    // after the first block the file is closed: everything written is flushed and can be read by a new reader
+    let python = run_python_with(
+        "with open(path, 'w') as file:\n    file.write('iron\\ngold\\n')\n\nwith open(path) as check:\n    print(check.read().count('\\n'))\n\nwith open(path) as file:\n    result = [line.rstrip('\\n') for line in file]",
+        |_, variables| variables.set_item("path", path),
+    );
+
+    let rust = run_rust(|stdout| {
+        {
+            let mut file = std::io::BufWriter::new(File::create(path)?);
+            file.write_all(b"iron\ngold\n")?;
+        } // <- `file` is dropped: the `BufWriter` flushes its buffer and the `File` is closed
+
+        writeln!(stdout, "{}", std::fs::read_to_string(path)?.lines().count()).unwrap();
+
+        let file = std::io::BufReader::new(File::open(path)?);
+
+        file.lines().collect::<std::io::Result<Vec<String>>>()
+    });
+
+    compare(python, rust).assert_equivalent();
+
+    // an error while opening ends both, the file of the first block is still closed
+    let python = run_python_with(
+        "with open(path + '/missing') as file:\n    result = file.read()",
+        |_, variables| variables.set_item("path", path),
+    );
+
+    let rust = run_rust(|_| std::fs::read_to_string(format!("{path}/missing")));
+
+    compare(python, rust).assert_equivalent();
+}
@@ -0,0 +1,373 @@
+//! https://doc.rust-lang.org/reference/type-layout.html
+//! https://cheats.rs/#memory-layout
+//!
+//! Every type has a size (bytes it occupies) and an alignment (its address has to be a multiple of the alignment).
+//! The size is always a multiple of the alignment, gaps between fields are filled with padding.
+//!
+//! The default representation `#[repr(Rust)]` makes no guarantees: the compiler is free to reorder fields to minimise padding.
+//! `#[repr(C)]` lays out fields in declaration order like a C compiler would, which is required for FFI (see `ffi.rs`).
+//! `#[repr(u8)]` (or any other integer) fixes the size of an enum's discriminant.
+//! `#[repr(transparent)]` guarantees that a single field wrapper has exactly the layout of its field.
+//!
+//! A niche is a bit pattern a type can never have (a null `&T`, a `bool` of 2, a `char` above `0x10FFFF`).
+//! Enums store their discriminant in the niche of a field instead of adding a separate one,
+//! which is why `Option<&T>` is just a nullable pointer.
+//!
+//! [`layout!`](crate::layout!) reports the size, alignment, field offsets (with `offset_of!`) and niche of a type as a [`TypeLayout`].
+
+use std::mem::{align_of, size_of};
+
+/// The layout of a type, built by [`layout!`](crate::layout!).
+/// `Display` prints it with one line per field:
+///
+/// ```
+/// use rust_snippets::layout;
+///
+/// #[allow(dead_code)]
+/// struct Point(f32, f32);
+///
+/// let point = layout!(Point { 0, 1 });
+///
+/// assert_eq!(point.padding(), Some(0));
+/// assert_eq!(
+///     point.to_string(),
+///     "Point: size 8, align 4, padding 0, niche no\n  .0: offset 0, size 4\n  .1: offset 4, size 4"
+/// );
+///
+/// let option = layout!(Option<Box<Point>>);
+///
+/// assert_eq!(option.padding(), None); // <- no fields listed
+/// assert_eq!(option.to_string(), format!("Option<Box<Point>>: size {0}, align {0}, niche no", size_of::<usize>()));
+/// ```
+#[derive(Debug)]
+pub struct TypeLayout {
+    pub name: &'static str,
+    pub size: usize,
+    pub align: usize,
+    /// Whether the type has a niche: `Option<T>` is as big as `T`.
+    pub niche: bool,
+    /// The fields that were listed, in that order.
+    pub fields: Vec<FieldLayout>,
+}
+
+#[derive(Debug)]
+pub struct FieldLayout {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl TypeLayout {
+    /// The layout without fields.
+    pub fn of<T>(name: &'static str) -> Self {
+        Self {
+            name,
+            size: size_of::<T>(),
+            align: align_of::<T>(),
+            niche: size_of::<Option<T>>() == size_of::<T>(), // <- `None` fits into a bit pattern `T` can't have
+            fields: Vec::new(),
+        }
+    }
+
+    /// Bytes that belong to no field, `None` if no fields are listed. Only meaningful if all fields are listed.
+    pub fn padding(&self) -> Option<usize> {
+        if self.fields.is_empty() {
+            return None;
+        }
+
+        Some(self.size - self.fields.iter().map(|field| field.size).sum::<usize>())
+    }
+
+    pub fn offsets(&self) -> Vec<usize> {
+        self.fields.iter().map(|field| field.offset).collect()
+    }
+}
+
+impl std::fmt::Display for TypeLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: size {}, align {}", self.name, self.size, self.align)?;
+
+        if let Some(padding) = self.padding() {
+            write!(f, ", padding {padding}")?;
+        }
+
+        write!(f, ", niche {}", if self.niche { "yes" } else { "no" })?;
+
+        for field in &self.fields {
+            write!(
+                f,
+                "\n  .{}: offset {}, size {}",
+                field.name, field.offset, field.size
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The size of a field, its type is inferred from the projection `|value: *const T| &raw const (*value).field`, which is never called.
+/// A raw pointer instead of `|value: T| value.field`: moving a field out of a type that implements `Drop` doesn't compile
+/// and a reference to a field of a packed struct isn't allowed.
+#[doc(hidden)] // <- used by `layout!`
+pub fn field_size<T, F>(_field: fn(*const T) -> *const F) -> usize {
+    size_of::<F>()
+}
+
+/// `layout!(Type)` for the [`TypeLayout`] of a type, `layout!(Type { field, .. })` with the offsets and sizes of the fields
+/// (in the given order, tuple structs use `0`, `1`, ..).
+///
+/// ```
+/// use rust_snippets::layout;
+///
+/// #[allow(dead_code)]
+/// struct Guard {
+///     id: u8,
+///     name: String,
+/// }
+///
+/// impl Drop for Guard {
+///     fn drop(&mut self) {}
+/// }
+///
+/// let guard = layout!(Guard { id, name });
+///
+/// assert_eq!(guard.size, size_of::<String>() + guard.align); // <- the `u8` is padded to the alignment of `String`
+/// assert_eq!(guard.padding(), Some(guard.align - 1));
+/// assert!(guard.niche);
+/// ```
+#[macro_export]
+macro_rules! layout {
+    ($ty:ty) => {
+        $crate::layout::TypeLayout::of::<$ty>(stringify!($ty))
+    };
+    ($ty:ty { $($field:tt),* $(,)? }) => {
+        $crate::layout::TypeLayout {
+            fields: ::std::vec![$($crate::layout::FieldLayout {
+                name: stringify!($field),
+                offset: ::core::mem::offset_of!($ty, $field),
+                // SAFETY: the closure is never called, it only names the type of the field
+                size: $crate::layout::field_size(|value: *const $ty| unsafe { &raw const (*value).$field }),
+            }),*],
+            ..$crate::layout::TypeLayout::of::<$ty>(stringify!($ty))
+        }
+    };
+}
+
+/// The layouts of types used in the other lessons.
+/// Sizes that contain pointers are checked for 64 bit targets.
+#[test]
+fn lesson_types() {
+    use indoc::indoc;
+
+    #[allow(dead_code)]
+    struct Point(f32, f32); // traits.rs
+
+    let point = layout!(Point { 0, 1 });
+
+    assert_eq!(
+        point.to_string(),
+        indoc! {"
+            Point: size 8, align 4, padding 0, niche no
+              .0: offset 0, size 4
+              .1: offset 4, size 4"
+        }
+    );
+
+    // every bit pattern of `f32` is a valid float -> no niche -> `Option<Point>` needs an extra discriminant (padded to the alignment)
+    assert_eq!(size_of::<Option<Point>>(), 12);
+
+    #[allow(dead_code)]
+    struct Animal {
+        name: String,
+        age: u32,
+    } // traits.rs
+
+    let animal = layout!(Animal { name, age });
+
+    #[cfg(target_pointer_width = "64")]
+    {
+        assert_eq!(animal.size, 32); // `String` is pointer, capacity and length (24 bytes), `u32` (4 bytes) and padding (4 bytes)
+        assert_eq!(animal.padding(), Some(4));
+    }
+    assert_eq!(animal.align, 8);
+    assert!(animal.niche); // `String` contains a non-null pointer
+
+    #[allow(dead_code)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    } // matching.rs
+
+    let color = layout!(Color);
+
+    assert_eq!(color.size, 1); // the discriminant fits into a byte, the variants carry no data
+    assert!(color.niche); // 253 of the 256 values of the byte are unused
+
+    #[allow(dead_code)]
+    enum Dead {
+        Yes,
+        No,
+    } // generics.rs
+
+    assert_eq!(layout!(Dead).size, 1);
+    assert_eq!(size_of::<Dead>(), size_of::<bool>()); // a two variant enum is laid out like a `bool`
+
+    let option = layout!(Option<i32>);
+
+    assert_eq!(option.size, 8); // every bit pattern is a valid `i32`, the discriminant needs its own (padded) space
+    assert_eq!(option.align, 4);
+    assert!(option.niche); // the discriminant itself has unused values
+    assert_eq!(size_of::<Option<Option<i32>>>(), 8); // -> nested options reuse the discriminant
+}
+
+/// The same fields in different representations.
+#[test]
+fn representations() {
+    #[allow(dead_code)]
+    struct RustLayout {
+        a: u8,
+        b: u32,
+        c: u16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct CLayout {
+        a: u8,
+        b: u32,
+        c: u16,
+    }
+
+    let rust = layout!(RustLayout { a, b, c });
+    let c = layout!(CLayout { a, b, c });
+
+    // the compiler reorders the fields to `b, c, a` (+ 1 byte padding), the exact order is not guaranteed
+    assert_eq!(rust.size, 8);
+    assert_eq!(rust.padding(), Some(1));
+
+    // C keeps the declaration order: `a` (1) + padding (3) + `b` (4) + `c` (2) + padding (2)
+    assert_eq!(c.size, 12);
+    assert_eq!(c.padding(), Some(5));
+    assert_eq!(c.offsets(), vec![0, 4, 8]);
+
+    // the discriminant of a `repr(Rust)` enum is as small as possible, `repr(C)` uses a C `int`, `repr(u*)` the given integer
+    #[allow(dead_code)]
+    enum RustEnum {
+        A,
+        B,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    enum CEnum {
+        A,
+        B,
+    }
+
+    #[allow(dead_code)]
+    #[repr(u8)]
+    enum U8Enum {
+        A = 1,
+        B = 200, // <- explicit discriminants are stored as written
+    }
+
+    #[allow(dead_code)]
+    #[repr(u32)]
+    enum U32Enum {
+        A,
+        B,
+    }
+
+    assert_eq!(layout!(RustEnum).size, 1);
+    assert_eq!(layout!(CEnum).size, size_of::<std::ffi::c_int>());
+    assert_eq!(layout!(U8Enum).size, 1);
+    assert_eq!(U8Enum::B as u8, 200);
+    assert_eq!(layout!(U32Enum).size, 4);
+
+    // `repr(u8)` on an enum with data: every variant starts with a `u8` tag, the data follows at its alignment
+    #[allow(dead_code)]
+    #[repr(u8)]
+    enum Shape {
+        Circle(f32),
+        Square(u16),
+    }
+
+    assert_eq!(layout!(Shape).size, 8); // tag (1) + padding (3) + `f32` (4)
+
+    // `repr(transparent)` newtypes have the exact layout (and ABI) of their field, including its niche
+    #[allow(dead_code)]
+    #[repr(transparent)]
+    struct Meters(f64);
+
+    #[allow(dead_code)]
+    #[repr(transparent)]
+    struct Handle(Box<i32>);
+
+    let meters = layout!(Meters);
+    let f64_layout = layout!(f64);
+
+    assert_eq!(
+        (meters.size, meters.align),
+        (f64_layout.size, f64_layout.align)
+    );
+    assert!(layout!(Handle).niche);
+
+    // `repr(packed)` removes the padding, fields can then be unaligned (references to them are not allowed)
+    #[allow(dead_code)]
+    #[repr(C, packed)]
+    struct Packed {
+        a: u8,
+        b: u32,
+        c: u16,
+    }
+
+    let packed = layout!(Packed { a, b, c });
+
+    assert_eq!(packed.size, 7);
+    assert_eq!(packed.align, 1);
+    assert_eq!(packed.offsets(), vec![0, 1, 5]);
+}
+
+/// The niche optimisation is guaranteed for `Option` of references, `Box`, `NonNull`, function pointers and `NonZero*` integers.
+/// `static_assertions` checks these facts at compile time, a wrong assertion is a compile error instead of a failing test.
+#[test]
+fn niches() {
+    use std::num::NonZeroU32;
+    use std::ptr::NonNull;
+
+    struct Molecule {
+        _weight: f32,
+    }
+
+    assert_eq_size!(Option<Box<Molecule>>, Box<Molecule>);
+    assert_eq_size!(Option<&Molecule>, &Molecule);
+    assert_eq_size!(Option<NonNull<Molecule>>, *mut Molecule);
+    assert_eq_size!(Option<fn(i32) -> i32>, fn(i32) -> i32);
+    assert_eq_size!(Option<NonZeroU32>, u32);
+
+    // not guaranteed, but true in practice: `Vec` and `String` contain a non-null pointer
+    assert_eq_size!(Option<Vec<Molecule>>, Vec<Molecule>);
+    assert_eq_size!(Option<String>, String);
+
+    // types with invalid bit patterns
+    assert_eq_size!(Option<bool>, bool); // 2..=255
+    assert_eq_size!(Option<char>, char); // above 0x10FFFF and surrogates
+
+    // no niche: every bit pattern is valid
+    const_assert!(size_of::<Option<u32>>() > size_of::<u32>());
+    const_assert!(size_of::<Option<f64>>() > size_of::<f64>());
+
+    // zero sized types take no space at all
+    assert_eq_size!((), [u8; 0]);
+    const_assert_eq!(size_of::<()>(), 0);
+
+    // the report shows the same facts at runtime
+    assert!(layout!(Box<Molecule>).niche);
+    assert!(!layout!(Option<Box<Molecule>>).niche); // the only niche of `Box` (null) is taken by the `Option`
+    assert_eq!(
+        size_of::<Option<Option<Box<Molecule>>>>(),
+        2 * size_of::<usize>()
+    ); // -> a second `Option` needs its own discriminant
+    assert!(!layout!(u32).niche);
+}
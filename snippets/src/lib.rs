@@ -3,24 +3,68 @@
 //!
 //! You can also try out the Rust Book experiment with interactive quizzes:
 //! https://rust-book.cs.brown.edu/
+//!
+//! # Course
+//!
+//! `cargo doc -p rust_snippets --open` renders the snippets as a course with one chapter per module.
+//! The module documentation explains the topic and the public items of a module demonstrate it with examples
+//! that `cargo test --doc` compiles and runs, including examples that have to panic (`should_panic`) or must not compile (`compile_fail`).
+//! The rest of the snippets are `#[test]` functions, which rustdoc doesn't render: follow the `source` link of a chapter to read them.
+//!
+//! 1. [`variables`]: mutability, shadowing, constants and type inference
+//! 2. [`print`](mod@print): formatting with `println!`
+//! 3. [`functions`]: functions, function pointers, closures and the `Fn` traits
+//! 4. [`matching`]: `match` on enums, `Option` and `Result`, guards, `@` bindings, destructuring, `let else` and `while let`
+//! 5. [`references`]: borrowing and shared ownership
+//! 6. [`raii`]: destructors, drop order and guards that clean up
+//! 7. [`lifetimes`]: lifetime annotations, elision and variance
+//! 8. [`casting`]: `as`, `From` and `TryFrom`
+//! 9. [`errror_handling`]: `Result`, `?` and panics
+//! 10. [`io`]: `Read`, `Write`, `BufRead` and `Seek`, files and directories
+//! 11. [`process`]: running programs, pipes, exit statuses and timeouts
+//! 12. [`traits`]: shared behaviour and operator overloading
+//! 13. [`generics`]: generic types, associated types, const generics and the orphan rule
+//! 14. [`dyn_dispatch`]: trait objects and vtables
+//! 15. [`macros`]: declarative and procedural macros
+//! 16. [`layout`]: size, alignment and niches of types
+//! 17. [`features`]: conditional compilation and cargo features
+//! 18. [`property_testing`]: testing properties instead of examples
+//! 19. [`unsafe_rust`]: raw pointers, `MaybeUninit`, unions and safe abstractions
+//! 20. [`ub_lab`]: undefined behaviour under Miri
+//! 21. [`safety`], [`cpp`] and [`ffi`]: C and C++ comparisons and calling C (`--features cc`)
+//! 22. `pyo3` and `numpy`: calling Rust from Python and Python from Rust (`--features python`)
+
+#![allow(rustdoc::bare_urls)] // the chapters start with plain links to the Rust book
 
 #[macro_use]
 extern crate static_assertions;
 
-mod casting;
-mod errror_handling;
-mod ffi;
-mod functions;
-mod generics;
-mod lifetimes;
-mod macros;
-mod matching;
+pub mod casting;
+pub mod cpp;
+pub mod dyn_dispatch;
+pub mod errror_handling;
+pub mod features;
+pub mod ffi;
+pub mod functions;
+pub mod generics;
+#[cfg(test)]
+mod harness; // <- compares lessons with their Python and C versions, only needed by tests
+pub mod io;
+pub mod layout;
+pub mod lifetimes;
+pub mod macros;
+pub mod matching;
 #[cfg(feature = "python")]
-mod numpy;
-mod print;
+pub mod numpy;
+pub mod print;
+pub mod process;
+pub mod property_testing;
 #[cfg(feature = "python")]
-mod pyo3;
-mod references;
-mod safety;
-mod traits;
-mod variables;
+pub mod pyo3;
+pub mod raii;
+pub mod references;
+pub mod safety;
+pub mod traits;
+pub mod ub_lab;
+pub mod unsafe_rust;
+pub mod variables;
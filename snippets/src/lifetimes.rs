@@ -4,25 +4,459 @@
 //! might sometimes return the first reference and sometimes the second one.
 //! It is impossible to predict which reference will be returned for a given call.
 //!  Explicit lifetimes help to avoid or clarify such a situation.
+//!
+//! https://doc.rust-lang.org/book/ch10-03-lifetime-syntax.html
+//!
+//! The borrow checker rejects the following conflicts at compile time.
+//!
+//! Without explicit lifetimes the compiler can't know which input the result borrows from:
+//!
+//! ```compile_fail,E0106
+//! fn greater(x: &u32, y: &u32) -> &u32 {
+//!     if x > y { x } else { y }
+//! }
+//! ```
+//!
+//! If the result can be either input, it can't outlive any of them:
+//!
+//! ```compile_fail,E0597
+//! fn greater<'a>(x: &'a u32, y: &'a u32) -> &'a u32 {
+//!     if x > y { x } else { y }
+//! }
+//!
+//! let x = 1;
+//!
+//! let z: &u32 = {
+//!     let y = 2;
+//!     greater(&x, &y)
+//! }; // y is dropped here while it is still borrowed by z
+//!
+//! assert_eq!(z, &2);
+//! ```
+//!
+//! If the result is declared to borrow only from `x`, returning `y` is rejected:
+//!
+//! ```compile_fail
+//! fn greater<'a, 'b>(x: &'a u32, y: &'b u32) -> &'a u32 {
+//!     if x > y { x } else { y } // lifetime may not live long enough
+//! }
+//! ```
+//!
+//! A struct holding a reference can't outlive the referenced value:
+//!
+//! ```compile_fail,E0597
+//! struct Excerpt<'a> {
+//!     part: &'a str,
+//! }
+//!
+//! let excerpt = {
+//!     let novel = String::from("Call me Ishmael. Some years ago...");
+//!     Excerpt { part: novel.split('.').next().unwrap() }
+//! };
+//!
+//! println!("{}", excerpt.part);
+//! ```
+//!
+//! `T: 'static` rejects types that borrow from a local variable:
+//!
+//! ```compile_fail,E0597
+//! fn keep<T: 'static>(value: T) -> Box<dyn std::any::Any> {
+//!     Box::new(value)
+//! }
+//!
+//! let local = String::from("local");
+//! keep(&local);
+//! ```
+//!
+//! `&mut T` is invariant in `T` -> a `&mut &'static str` can't be used to store a shorter lived reference:
+//!
+//! ```compile_fail,E0597
+//! fn assign<'a>(target: &mut &'a str, value: &'a str) {
+//!     *target = value;
+//! }
+//!
+//! let mut name: &'static str = "static";
+//!
+//! {
+//!     let local = String::from("local");
+//!     assign(&mut name, &local);
+//! }
+//!
+//! println!("{name}");
+//! ```
+//!
+//! A struct can't hold a value and a reference into that value (self-referential struct):
+//!
+//! ```compile_fail,E0505
+//! struct Parsed<'a> {
+//!     text: String,
+//!     first_word: &'a str,
+//! }
+//!
+//! let text = String::from("hello world");
+//! let first_word = text.split(' ').next().unwrap();
+//!
+//! let parsed = Parsed { text, first_word }; // `text` is moved while `first_word` borrows it
+//!
+//! println!("{}", parsed.first_word);
+//! ```
+//!
+//! A collection can't be modified while an iterator borrows it:
+//!
+//! ```compile_fail,E0502
+//! let mut values = vec![1, 2, 3];
+//!
+//! for value in values.iter() {
+//!     values.push(*value);
+//! }
+//! ```
+
+/// Returns the longer of two strings (the first one if both are equally long).
+/// The result borrows from both arguments, so it can only be used while both are alive (`'a` is the overlap of both lifetimes).
+///
+/// ```
+/// use rust_snippets::lifetimes::longest;
+///
+/// let iron = String::from("iron");
+///
+/// {
+///     let copper = String::from("copper");
+///     let result = longest(&iron, &copper);
+///
+///     assert_eq!(result, "copper"); // <- used while both strings are alive
+/// }
+/// ```
+///
+/// ```compile_fail,E0597
+/// use rust_snippets::lifetimes::longest;
+///
+/// let iron = String::from("iron");
+/// let result;
+///
+/// {
+///     let copper = String::from("copper");
+///     result = longest(&iron, &copper); // <- `copper` does not live long enough
+/// }
+///
+/// println!("{result}");
+/// ```
+pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() >= y.len() {
+        x
+    } else {
+        y
+    }
+}
 
-/// With explicit lifetimes the compiler knows that the result of greater has the same lifetime as the first argument ('a)
-/// and can outlive the second argument. If y had the same lifetime the compiler would complain that y does not live long enough.
+/// With explicit lifetimes the compiler knows that the result of greater lives as long as the shorter lived argument ('a is the overlap of both)
+/// and therefore can't outlive either of them. If y went out of scope before z is used, the compiler would complain that y does not live long enough (see the module documentation).
+/// A function that only ever returns the first argument can give the second argument a separate lifetime ('b) and the result can outlive it.
+#[allow(clippy::needless_lifetimes)] // 'b could be elided but is spelled out on purpose
 #[test]
 pub fn epxlicit_lifetimes() {
-    fn greater<'a, 'b>(x: &'a u32, y: &'b u32) -> &'a u32 {
+    fn greater<'a>(x: &'a u32, y: &'a u32) -> &'a u32 {
         if x > y {
             x
         } else {
-            &0
+            y
         }
     }
 
-    let x = 1; // x is dropped at the end of the function
+    let x = 1;
+    let y = 2;
 
-    let z: &u32 = {
+    let z: &u32 = greater(&x, &y);
+
+    assert_eq!(z, &2); // z is a reference to y -> x and y have to be alive here
+
+    fn first_if_greater<'a, 'b>(x: &'a u32, y: &'b u32) -> Option<&'a u32> {
+        if x > y {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    let x = 3;
+
+    let z: Option<&u32> = {
         let y = 2;
-        greater(&x, &y)
-    }; // y is dropped here
+        first_if_greater(&x, &y)
+    }; // y is dropped here, the result only borrows x
+
+    assert_eq!(z, Some(&3));
+}
+
+/// https://doc.rust-lang.org/reference/lifetime-elision.html
+///
+/// Rule 1: Every elided lifetime in the arguments becomes a separate lifetime parameter.
+/// `fn len(s: &str) -> usize` is the same as `fn len<'a>(s: &'a str) -> usize`
+#[allow(clippy::needless_lifetimes)] // the elided and explicit versions are compared
+#[test]
+fn elision_rule_inputs() {
+    fn count(a: &str, b: &str) -> usize {
+        a.len() + b.len()
+    }
+
+    fn count_explicit<'a, 'b>(a: &'a str, b: &'b str) -> usize {
+        a.len() + b.len()
+    }
+
+    let a = String::from("Fe");
+
+    let counted = {
+        let b = String::from("Cu"); // different lifetimes are fine, nothing is returned by reference
+        (count(&a, &b), count_explicit(&a, &b))
+    };
+
+    assert_eq!(counted, (4, 4));
+}
+
+/// Rule 2: If there is exactly one input lifetime, it is assigned to all output lifetimes.
+/// `fn first_word(s: &str) -> &str` is the same as `fn first_word<'a>(s: &'a str) -> &'a str`
+/// With two reference arguments and no `self`, the output lifetime can't be elided (see the module documentation).
+#[allow(clippy::needless_lifetimes)] // the elided and explicit versions are compared
+#[test]
+fn elision_rule_single_input() {
+    fn first_word(s: &str) -> &str {
+        s.split(' ').next().unwrap_or("")
+    }
+
+    fn first_word_explicit<'a>(s: &'a str) -> &'a str {
+        s.split(' ').next().unwrap_or("")
+    }
+
+    // the second argument is not a reference -> still only one input lifetime
+    fn nth_word(s: &str, n: usize) -> Option<&str> {
+        s.split(' ').nth(n)
+    }
+
+    let text = String::from("hello lifetime world");
+
+    assert_eq!(first_word(&text), "hello");
+    assert_eq!(first_word_explicit(&text), "hello");
+    assert_eq!(nth_word(&text, 1), Some("lifetime"));
+}
+
+/// Rule 3: If there are multiple input lifetimes, but one of them is `&self` or `&mut self`, the lifetime of `self` is assigned to all output lifetimes.
+/// Methods usually return data borrowed from `self`, so this is the common case.
+#[test]
+fn elision_rule_self() {
+    struct Dictionary {
+        words: Vec<String>,
+    }
+
+    impl Dictionary {
+        // same as `fn find<'s, 'p>(&'s self, prefix: &'p str) -> Option<&'s str>`
+        fn find(&self, prefix: &str) -> Option<&str> {
+            self.words
+                .iter()
+                .find(|word| word.starts_with(prefix))
+                .map(|word| word.as_str())
+        }
+    }
+
+    let dictionary = Dictionary {
+        words: vec!["iron".to_string(), "gold".to_string()],
+    };
+
+    let found = {
+        let prefix = String::from("go");
+        dictionary.find(&prefix)
+    }; // `prefix` is dropped here, the result only borrows `dictionary`
+
+    assert_eq!(found, Some("gold"));
+}
+
+/// A struct that holds a reference needs a lifetime parameter. The struct can't outlive the referenced data (see the module documentation).
+/// Methods returning the reference can return it with the lifetime of the data ('a) instead of the lifetime of the struct (&self).
+#[test]
+fn struct_with_reference() {
+    struct Excerpt<'a> {
+        part: &'a str,
+    }
+
+    impl<'a> Excerpt<'a> {
+        fn part(&self) -> &'a str {
+            self.part // lives as long as the novel, not as long as the excerpt
+        }
+    }
+
+    let novel = String::from("Call me Ishmael. Some years ago...");
+
+    let part = {
+        let excerpt = Excerpt {
+            part: novel.split('.').next().unwrap(),
+        };
+
+        excerpt.part()
+    }; // `excerpt` is dropped here, `part` borrows from `novel`
+
+    assert_eq!(part, "Call me Ishmael");
+}
+
+/// `&'static T` is a reference that is valid for the entire program (string literals, statics, leaked memory).
+/// `T: 'static` is a bound meaning `T` does not borrow anything that could expire -> owned types like `String` are `'static`
+/// even though a `String` value doesn't live forever. It is required for example by `std::thread::spawn`.
+#[test]
+fn static_lifetime() {
+    let literal: &'static str = "Fe"; // string literals are stored in the binary -> `'static`
+
+    static ELEMENTS: [&str; 2] = ["Fe", "Cu"];
+
+    let element: &'static str = ELEMENTS[1];
+
+    let leaked: &'static str = Box::leak(String::from("Au").into_boxed_str()); // leaking heap memory makes it `'static`
+
+    assert_eq!([literal, element, leaked], ["Fe", "Cu", "Au"]);
+
+    fn is_static<T: 'static>(_value: &T) -> bool {
+        true
+    }
+
+    let owned = String::from("owned");
+
+    assert!(is_static(&owned)); // `String: 'static` although `owned` is dropped at the end of this function
+    assert!(is_static(&literal)); // `&'static str: 'static`
+
+    // is_static(&owned.as_str()); // Error: `&str` borrowing `owned` is not `'static` (see the module documentation)
+
+    // threads can outlive the current function -> the closure has to be `'static` -> `move` the owned value into it
+    let handle = std::thread::spawn(move || owned.len());
+
+    assert_eq!(handle.join().unwrap(), 5);
+}
+
+/// https://doc.rust-lang.org/nomicon/subtyping.html
+///
+/// Lifetimes have a subtyping relationship: `'long` is a subtype of `'short` if `'long` outlives `'short`.
+/// `&'a T` is covariant in `'a` -> a longer lived reference can be used where a shorter lived one is expected.
+/// `&mut T` is invariant in `T` -> `&mut &'static str` can't be used as `&mut &'short str`, otherwise a short lived reference could be written into a `'static` slot (see the module documentation).
+#[test]
+fn variance() {
+    fn shorter<'a>(a: &'a str, _b: &'a str) -> &'a str {
+        a
+    }
+
+    let long_lived: &'static str = "static";
+    let short = String::from("short");
+
+    let result = shorter(long_lived, &short); // `&'static str` is shortened to the lifetime of `short` (covariance)
+
+    assert_eq!(result, "static");
+
+    fn assign<'a>(target: &mut &'a str, value: &'a str) {
+        *target = value;
+    }
+
+    let mut name: &str = "static"; // the lifetime of `name` is inferred to be short enough for `local`
+    let local = String::from("local");
+
+    assign(&mut name, &local);
+
+    assert_eq!(name, "local");
+}
+
+/// A struct can't hold a value and a reference into the same value, because moving the struct would move the value and invalidate the reference (see the module documentation).
+/// The common solutions are storing indices instead of references, splitting the owner and the borrower into two structs,
+/// or crates like `ouroboros` which use `Pin` and `unsafe` internally.
+#[test]
+fn self_referential_struct() {
+    // indices instead of references -> the struct owns everything and can be moved freely
+    struct Parsed {
+        text: String,
+        first_word: std::ops::Range<usize>,
+    }
+
+    impl Parsed {
+        fn new(text: String) -> Self {
+            let end = text.find(' ').unwrap_or(text.len());
+            Parsed {
+                text,
+                first_word: 0..end,
+            }
+        }
+
+        fn first_word(&self) -> &str {
+            &self.text[self.first_word.clone()]
+        }
+    }
+
+    let parsed = Parsed::new(String::from("hello world"));
+    let moved = parsed; // moving is fine, the range stays valid
+
+    assert_eq!(moved.first_word(), "hello");
+
+    // owner and borrower split into two structs -> the borrower's lifetime is tied to the owner
+    struct Document {
+        text: String,
+    }
+
+    struct Summary<'a> {
+        first_word: &'a str,
+    }
+
+    let document = Document {
+        text: String::from("hello world"),
+    };
+
+    let summary = Summary {
+        first_word: document.text.split(' ').next().unwrap(),
+    };
+
+    assert_eq!(summary.first_word, "hello");
+}
+
+/// Iterators often borrow the collection they iterate over. The items can borrow from the collection as well.
+/// An iterator that yields references needs a lifetime that ties the items to the data and not to the iterator.
+/// The collection can't be modified while it is borrowed by an iterator (see the module documentation).
+#[test]
+fn borrowing_iterators() {
+    struct Words<'a> {
+        text: &'a str,
+    }
+
+    impl<'a> Iterator for Words<'a> {
+        type Item = &'a str; // the items borrow the text, not the `Words` iterator
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let text = self.text.trim_start();
+
+            if text.is_empty() {
+                return None;
+            }
+
+            let end = text.find(' ').unwrap_or(text.len());
+            let (word, rest) = text.split_at(end);
+
+            self.text = rest;
+
+            Some(word)
+        }
+    }
+
+    let text = String::from("iron  gold copper");
+
+    let words: Vec<&str> = Words { text: &text }.collect(); // the iterator is dropped, the words borrow `text`
+
+    assert_eq!(words, ["iron", "gold", "copper"]);
+
+    // returning an iterator adaptor -> the returned iterator borrows `text` and `prefix`, expressed with `+ 'a`
+    fn starting_with<'a>(text: &'a str, prefix: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        text.split_whitespace()
+            .filter(move |word| word.starts_with(prefix))
+    }
+
+    let matches: Vec<&str> = starting_with(&text, "co").collect();
+
+    assert_eq!(matches, ["copper"]);
+
+    let mut values = vec![1, 2, 3];
+
+    // collecting first ends the borrow -> the collection can be modified afterwards
+    let doubled: Vec<i32> = values.iter().map(|value| value * 2).collect();
+
+    values.extend(doubled);
 
-    assert_eq!(z, &0); // a reference to y would not live long enough
+    assert_eq!(values, [1, 2, 3, 2, 4, 6]);
 }
@@ -6,6 +6,57 @@
 //! The downside to implementing a macro instead of a function is that macro definitions are more complex than function definitions.
 //!
 //! Another important difference between macros and functions is that you must define macros or bring them into scope before you call them in a file, as opposed to functions you can define anywhere and call anywhere.
+//!
+//! Macros are hygienic -> a macro can't declare a variable that is visible to the caller unless the caller passes the identifier in:
+//!
+//! ```compile_fail,E0425
+//! macro_rules! declare_iron {
+//!     () => {
+//!         let iron = "Fe";
+//!     };
+//! }
+//!
+//! declare_iron!();
+//!
+//! assert_eq!(iron, "Fe");
+//! ```
+//!
+//! `macro_rules!` macros are declarative: they match patterns and expand into code.
+//! Procedural macros are Rust functions that run at compile time on the tokens of the code (see the `snippets_derive` crate).
+//! They have to be defined in a separate crate and come in three kinds: derive macros, attribute macros and function-like macros.
+//!
+//! Procedural macros report unsupported input with `compile_error!`, which fails the compilation with a readable error:
+//!
+//! ```compile_fail
+//! use snippets_derive::Add;
+//!
+//! #[derive(Add)] // `#[derive(Add)]` is not supported for enums
+//! enum Color {
+//!     Red,
+//!     Green,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! use snippets_derive::Molecule;
+//!
+//! trait Molecule {
+//!     fn name(&self) -> String;
+//!     fn weight(&self) -> f32;
+//!     fn is_organic(&self) -> bool;
+//! }
+//!
+//! #[derive(Molecule)]
+//! #[molecule(name = "Water")] // missing `weight = ...`
+//! struct Water;
+//! ```
+//!
+//! ```compile_fail
+//! use snippets_derive::call_count;
+//!
+//! #[call_count] // expected `fn`
+//! struct Counter;
+//! ```
 
 /// The syntax `macro_rules! name_of_macro` starts the implementation of a macro available by the name `name_of_macro`
 /// and `#[macro_export]` makes this macro available to the entire crate
@@ -52,6 +103,584 @@ pub fn test_point_macro() {
     //println!("{:?}", point!(1.0, 1.0, 1.0));  // wrong input types!
 }
 
+/// The expansion of `point!` for any input, not only for `1`.
+#[test]
+pub fn point_macro_properties() {
+    use crate::property_testing::check;
+    use proptest::prelude::*;
+
+    // property: every arm keeps the number of arguments and their order
+    check(&(any::<i32>(), any::<i32>(), any::<i32>()), |(x, y, z)| {
+        prop_assert_eq!(point!(x, y), vec![x, y]);
+        prop_assert_eq!(point!(x, y, z), vec![x, y, z]);
+        prop_assert_eq!(point!(x, y, z, i64), vec![x as i64, y as i64, z as i64]);
+        prop_assert_eq!(
+            point!([z, y, x, y, z]: i64),
+            vec![z as i64, y as i64, x as i64, y as i64, z as i64]
+        );
+        Ok(())
+    });
+}
+
+/// Recursive macros call themselves with less input until a base case matches.
+/// `$crate::max!` refers to the macro through the crate it was defined in, so the recursion also works when the macro is used from another crate.
+///
+/// ```
+/// use rust_snippets::max;
+///
+/// assert_eq!(max!(3), 3);
+/// assert_eq!(max!(3, 1, 2), 3);
+/// assert_eq!(max!(1.5, 2.5), 2.5);
+/// ```
+#[macro_export]
+macro_rules! max {
+    // base case
+    ($x:expr) => {
+        $x
+    };
+    // recursive case -> the first element is compared against the maximum of the rest
+    ($x:expr, $($rest:expr),+) => {{
+        let a = $x;
+        let b = $crate::max!($($rest),+);
+        if a > b {
+            a
+        } else {
+            b
+        }
+    }};
+}
+
+#[test]
+pub fn recursive_macros() {
+    assert_eq!(max!(1), 1);
+    assert_eq!(max!(3, 1, 2), 3);
+    assert_eq!(max!(1.5, 2.5, -1.0, 0.0), 2.5);
+
+    // every argument is bound to a variable once (see the snapshot), so an expression with side effects is evaluated once (not in the comparison and the result)
+    let mut calls = 0;
+    let mut next = |value| {
+        calls += 1;
+        value
+    };
+
+    assert_eq!(max!(next(3), next(1), next(2)), 3);
+    assert_eq!(calls, 3);
+
+    assert_expansion("max", &["max"], "max!(3, 1, 2)");
+}
+
+/// Macros can't count with integers, counting is done by expanding every token tree into something countable.
+/// Recursion expands to `1 + 1 + 1 + 0` but is limited by the recursion limit (128 by default).
+/// The slice trick `<[()]>::len(&[(), (), ()])` replaces every token tree with `()` without recursion. Both are constant expressions.
+///
+/// ```
+/// use rust_snippets::count;
+///
+/// assert_eq!(count!(Fe Cu Au), 3);
+/// assert_eq!(count!(), 0);
+/// ```
+#[macro_export]
+macro_rules! count {
+    () => {
+        0usize
+    };
+    ($head:tt $($tail:tt)*) => {
+        1usize + $crate::count!($($tail)*)
+    };
+}
+
+#[macro_export]
+macro_rules! count_fast {
+    // internal rule -> replaces any token tree with `()`
+    (@unit $_t:tt) => {
+        ()
+    };
+    ($($t:tt)*) => {
+        <[()]>::len(&[$($crate::count_fast!(@unit $t)),*])
+    };
+}
+
+#[test]
+pub fn counting_macros() {
+    assert_eq!(count!(), 0);
+    assert_eq!(count!(a b c), 3);
+    assert_eq!(count!(Fe Cu (Au Ag)), 3); // `(Au Ag)` is a single token tree
+
+    const COUNT: usize = count_fast!(a b c d e f g h); // usable in constants
+    assert_eq!(COUNT, 8);
+
+    // both expansions are constant expressions, e.g. the length of an array
+    let elements: [&str; count!(Fe Cu Au)] = ["Fe", "Cu", "Au"];
+    let metals: [&str; count_fast!(Fe Cu)] = ["Fe", "Cu"];
+
+    assert_eq!((elements.len(), metals.len()), (3, 2));
+
+    assert_expansion("count", &["count"], "count!(a b c)");
+    assert_expansion("count_fast", &["count_fast"], "count_fast!(a b c)");
+}
+
+/// Internal rules are arms starting with a marker like `@count` that are only meant to be called by the macro itself.
+/// They act like private helper functions of the macro. They are usually placed first, so that they are tried before the public arms.
+#[macro_export]
+macro_rules! named_enum {
+    (@count) => {
+        0
+    };
+    (@count $head:ident $($tail:ident)*) => {
+        1 + $crate::named_enum!(@count $($tail)*)
+    };
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant),*
+        }
+
+        impl $name {
+            // the length of the array is computed by the internal rule
+            pub const ALL: [$name; $crate::named_enum!(@count $($variant)*)] = [$($name::$variant),*];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant)),*
+                }
+            }
+        }
+    };
+}
+
+#[test]
+pub fn internal_rules() {
+    named_enum! {
+        #[derive(Debug, PartialEq)]
+        enum Metal { Iron, Copper, Gold }
+    }
+
+    assert_eq!(Metal::ALL, [Metal::Iron, Metal::Copper, Metal::Gold]);
+    assert_eq!(Metal::Copper.name(), "Copper");
+
+    // the internal rule `@count` computes the length of `ALL`
+    assert_eq!(
+        Metal::ALL.map(|metal| metal.name()),
+        ["Iron", "Copper", "Gold"]
+    );
+
+    assert_expansion(
+        "named_enum",
+        &["named_enum"],
+        "named_enum! { enum Metal { Iron, Copper } }",
+    );
+}
+
+/// A tt muncher is a recursive macro that processes its input one token tree at a time ("munches" it) and calls itself with the rest.
+/// The state of the parser (here the stack of a reverse polish notation calculator) is passed along as part of the input.
+/// This allows parsing input that can't be described with a single pattern.
+///
+/// ```
+/// use rust_snippets::rpn;
+///
+/// assert_eq!(rpn!(2 3 + 4 *), (2 + 3) * 4);
+/// ```
+#[macro_export]
+macro_rules! rpn {
+    // the input is empty and one value is left on the stack -> result
+    (@stack [$result:expr]) => {
+        $result
+    };
+    // an operator pops two values and pushes the result, the top of the stack comes first
+    (@stack [$b:expr, $a:expr $(, $stack:expr)*] + $($rest:tt)*) => {
+        $crate::rpn!(@stack [($a + $b) $(, $stack)*] $($rest)*)
+    };
+    (@stack [$b:expr, $a:expr $(, $stack:expr)*] - $($rest:tt)*) => {
+        $crate::rpn!(@stack [($a - $b) $(, $stack)*] $($rest)*)
+    };
+    (@stack [$b:expr, $a:expr $(, $stack:expr)*] * $($rest:tt)*) => {
+        $crate::rpn!(@stack [($a * $b) $(, $stack)*] $($rest)*)
+    };
+    (@stack [$b:expr, $a:expr $(, $stack:expr)*] / $($rest:tt)*) => {
+        $crate::rpn!(@stack [($a / $b) $(, $stack)*] $($rest)*)
+    };
+    // a number is pushed onto the stack
+    (@stack [$($stack:expr),*] $number:literal $($rest:tt)*) => {
+        $crate::rpn!(@stack [$number $(, $stack)*] $($rest)*)
+    };
+    // entry point -> start with an empty stack
+    ($($tokens:tt)+) => {
+        $crate::rpn!(@stack [] $($tokens)+)
+    };
+}
+
+#[test]
+pub fn tt_muncher() {
+    assert_eq!(rpn!(2 3 +), 5);
+    assert_eq!(rpn!(2 3 + 4 *), 20);
+    assert_eq!(rpn!(10 2 8 * + 3 -), 23);
+    assert_eq!(rpn!(1.0 4.0 /), 0.25);
+
+    // rpn!(1 +); // Error: no rules expected the token `+` -> not enough values on the stack
+
+    // every operation is parenthesised (see the snapshot), the operands keep their order
+    assert_eq!(rpn!(2 3 4 + *), 14);
+    assert_eq!(rpn!(8 2 - 3 -), 3);
+    assert_eq!(rpn!(8 2 / 2 /), 2);
+
+    assert_expansion("rpn", &["rpn"], "rpn!(2 3 + 4 *)");
+}
+
+/// Macros are hygienic: variables declared inside a macro live in the "syntax context" of the macro and can't clash with variables of the caller.
+/// `point!` declares a local `vec`, and the caller's `vec` passed in as argument still refers to the caller's variable.
+/// A macro can only bind a variable visible to the caller if the caller passes in the identifier (see the module documentation).
+#[test]
+pub fn hygiene() {
+    let vec = vec![3, 4]; // the caller's `vec`
+
+    let point = point!(vec[0], vec[1]); // expands to `let mut vec = Vec::new(); vec.push(vec[0]); ...`
+
+    assert_eq!(point, vec![3, 4]);
+    assert_eq!(vec, vec![3, 4]); // the caller's `vec` is unchanged
+
+    macro_rules! declare {
+        ($name:ident = $value:expr) => {
+            let $name = $value; // the identifier comes from the caller -> visible to the caller
+        };
+    }
+
+    declare!(iron = "Fe");
+
+    assert_eq!(iron, "Fe");
+
+    let vec = 1;
+
+    assert_eq!(point!(vec, vec), [1, 1]);
+
+    // the expansion prints both variables as `vec`, the compiler keeps them apart by their syntax context
+    assert_expansion("hygiene", &["point"], "let vec = 1; point!(vec, vec)");
+}
+
+/// An element used through `$crate` by the `element!` macro.
+#[allow(dead_code)] // only constructed by `element!`
+#[derive(Debug, PartialEq)]
+pub struct Element {
+    pub symbol: &'static str,
+}
+
+/// `$crate` expands to the path of the crate that defines the macro.
+/// Paths in exported macros should always start with `$crate` (or `::std`), otherwise they are resolved where the macro is called,
+/// where `Element` might not be imported or might be a different type.
+#[macro_export]
+macro_rules! element {
+    ($symbol:literal) => {
+        $crate::macros::Element { symbol: $symbol }
+    };
+}
+
+#[test]
+pub fn crate_paths() {
+    mod other_module {
+        // `Element` is not imported here, but `$crate::macros::Element` is found anyway
+        pub fn iron() -> crate::macros::Element {
+            element!("Fe")
+        }
+    }
+
+    struct Element; // a different `Element` in the scope of the caller doesn't matter
+
+    let _ = Element;
+
+    assert_eq!(other_module::iron().symbol, "Fe");
+
+    assert_expansion_with_items(
+        "element",
+        &["element"],
+        "mod macros { pub struct Element { pub symbol: &'static str } }",
+        "let _ = element!(\"Fe\")",
+    );
+}
+
+/// Macros can define small domain specific languages (DSL) with their own syntax, as long as it consists of valid Rust tokens.
+/// `hashmap!` adds the `key => value` syntax for `HashMap` literals, which the standard library only has for `vec!`.
+///
+/// ```
+/// use rust_snippets::hashmap;
+///
+/// let weights = hashmap! {
+///     "Fe" => 55.845,
+///     "Au" => 196.97,
+/// };
+///
+/// assert_eq!(weights["Au"], 196.97);
+/// assert_eq!(weights.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! hashmap {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        let mut map = ::std::collections::HashMap::with_capacity($crate::count_fast!($($key)*));
+        $(
+            map.insert($key, $value);
+        )*
+        map
+    }};
+}
+
+/// A state machine DSL: the states, events and transitions `State + Event => NextState` are declared in one place
+/// and the macro generates the enums and the transition function. Attributes like `#[derive(...)]` are passed on to the enums.
+#[macro_export]
+macro_rules! state_machine {
+    (
+        $(#[$state_meta:meta])* $state:ident { $($states:ident),* $(,)? }
+        $(#[$event_meta:meta])* $event:ident { $($events:ident),* $(,)? }
+        $($from:ident + $on:ident => $to:ident),* $(,)?
+    ) => {
+        $(#[$state_meta])*
+        enum $state {
+            $($states),*
+        }
+
+        $(#[$event_meta])*
+        enum $event {
+            $($events),*
+        }
+
+        impl $state {
+            fn next(self, event: $event) -> Option<$state> {
+                match (self, event) {
+                    $(($state::$from, $event::$on) => Some($state::$to),)*
+                    _ => None, // every other combination is not a valid transition
+                }
+            }
+        }
+    };
+}
+
+#[test]
+pub fn dsl_macros() {
+    let weights = hashmap! {
+        "Fe" => 55.845,
+        "Cu" => 63.546,
+    };
+
+    assert_eq!(weights["Fe"], 55.845);
+    assert_eq!(weights.len(), 2);
+    assert!(weights.capacity() >= 2); // <- the capacity is computed by `count_fast!`
+
+    state_machine! {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        Door { Open, Closed, Locked }
+        Action { Push, Pull, Lock, Unlock }
+
+        Closed + Push => Open,
+        Open + Pull => Closed,
+        Closed + Lock => Locked,
+        Locked + Unlock => Closed,
+    }
+
+    let door = Door::Locked;
+
+    assert_eq!(door.next(Action::Push), None); // a locked door can't be opened
+
+    let door = door.next(Action::Unlock).unwrap();
+    let door = door.next(Action::Push).unwrap();
+
+    assert_eq!(door, Door::Open);
+    assert_eq!(door.next(Action::Lock), None); // an open door can't be locked
+    assert_eq!(door.next(Action::Pull), Some(Door::Closed));
+
+    assert_expansion(
+        "hashmap",
+        &["hashmap", "count_fast"],
+        "let _ = hashmap! { \"Fe\" => 55.845, \"Cu\" => 63.546 }",
+    );
+    assert_expansion(
+        "state_machine",
+        &["state_machine"],
+        "state_machine! { Door { Open, Closed } Action { Push, Pull } Closed + Push => Open, Open + Pull => Closed }",
+    );
+}
+
+/// `cargo expand` equivalent for the lessons: expands `invocation` with the `macro_rules!` definitions of this file
+/// by running `rustc -Zunpretty=expanded` (`RUSTC_BOOTSTRAP=1` allows the unstable flag on a stable toolchain).
+/// The expansion is compared with the snapshot in `snapshots/macros/{name}.rs`, `UPDATE_SNAPSHOTS=1 cargo test` accepts a changed expansion.
+/// If `rustc` doesn't accept `-Zunpretty` the check skips (with a note on stderr) instead of failing, the behaviour is asserted anyway.
+#[cfg(test)]
+fn assert_expansion(name: &str, macros: &[&str], invocation: &str) {
+    assert_expansion_with_items(name, macros, "", invocation);
+}
+
+/// Same as `assert_expansion` with additional `items` (e.g. types used by the expansion) next to the expanded function.
+#[cfg(test)]
+fn assert_expansion_with_items(name: &str, macros: &[&str], items: &str, invocation: &str) {
+    let source = include_str!("macros.rs");
+
+    // the definition of every macro is copied from this file by matching the braces after `macro_rules! name`
+    let definitions: Vec<String> = macros
+        .iter()
+        .map(|name| {
+            let start = source
+                .find(&format!("macro_rules! {name} {{"))
+                .unwrap_or_else(|| panic!("no macro `{name}` in macros.rs"));
+
+            let mut depth = 0;
+            let end = source[start..]
+                .char_indices()
+                .find_map(|(i, c)| {
+                    match c {
+                        '{' => depth += 1,
+                        '}' if depth == 1 => return Some(start + i + 1),
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    None
+                })
+                .unwrap();
+
+            format!("#[macro_export]\n{}", &source[start..end])
+        })
+        .collect();
+
+    let Some(expanded) = expand(
+        name,
+        &format!(
+            "{}\n\n{items}\n\nfn expansion() {{\n{invocation};\n}}\n",
+            definitions.join("\n\n")
+        ),
+    ) else {
+        return;
+    };
+
+    let expansion = &expanded[expanded.find("fn expansion()").unwrap()..];
+
+    let path = format!("{}/snapshots/macros/{name}.rs", env!("CARGO_MANIFEST_DIR"));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(format!("{}/snapshots/macros", env!("CARGO_MANIFEST_DIR")))
+            .unwrap();
+        std::fs::write(&path, expansion).unwrap();
+    }
+
+    let snapshot = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing snapshot {path}, run with UPDATE_SNAPSHOTS=1"));
+
+    assert_eq!(
+        expansion, snapshot,
+        "the expansion changed, run with UPDATE_SNAPSHOTS=1 to accept it"
+    );
+}
+
+/// Runs `rustc -Zunpretty=expanded` on `source`.
+/// Returns `None` if `rustc` can't be started or doesn't accept the unstable flag.
+#[cfg(test)]
+fn expand(name: &str, source: &str) -> Option<String> {
+    let directory = std::env::temp_dir().join(format!(
+        "rust_snippets_expand_{name}_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let file = directory.join("expand.rs");
+    std::fs::write(&file, source).unwrap();
+
+    let output = std::process::Command::new("rustc")
+        .args([
+            "--edition",
+            "2021",
+            "--crate-type",
+            "lib",
+            "-Zunpretty=expanded",
+        ])
+        .arg(&file)
+        .env("RUSTC_BOOTSTRAP", "1")
+        .output();
+
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    let output = match output {
+        Ok(output) => output,
+        Err(error) => {
+            eprintln!("rustc could not be started ({error}), skipping the expansion of `{name}`");
+            return None;
+        }
+    };
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // e.g. "the option `Z` is only accepted on the nightly compiler" if `RUSTC_BOOTSTRAP` is disallowed
+    let unavailable = [
+        "is only accepted on the nightly compiler",
+        "unknown unstable option",
+    ];
+
+    if !output.status.success() && unavailable.iter().any(|message| stderr.contains(message)) {
+        eprintln!("rustc does not accept -Zunpretty=expanded, skipping the expansion of `{name}`: {stderr}");
+        return None;
+    }
+
+    assert!(output.status.success(), "{stderr}");
+
+    Some(String::from_utf8(output.stdout).unwrap())
+}
+
+/// Derive macros generate code from the definition of a struct or enum, usually a trait implementation.
+/// `#[derive(Add)]` from `snippets_derive` implements `Add` field by field, which the standard library doesn't provide.
+/// Helper attributes like `#[add(with = "concat")]` or `#[molecule(...)]` pass additional information to the macro.
+#[test]
+pub fn derive_macros() {
+    use snippets_derive::{Add, Molecule};
+
+    #[derive(Add, Debug, PartialEq)]
+    struct Point(f32, f32);
+
+    assert_eq!(Point(1.0, 2.0) + Point(3.0, 4.0), Point(4.0, 6.0));
+
+    // generic structs get the bound `T: Add<Output = T>`
+    #[derive(Add, Debug, PartialEq)]
+    struct Vector<T> {
+        x: T,
+        y: T,
+    }
+
+    assert_eq!(
+        Vector { x: 1, y: 2 } + Vector { x: 3, y: 4 },
+        Vector { x: 4, y: 6 }
+    );
+
+    trait Molecule {
+        fn name(&self) -> String;
+        fn weight(&self) -> f32;
+        fn is_organic(&self) -> bool;
+    }
+
+    #[derive(Molecule)]
+    #[molecule(weight = 18.015)] // `name` defaults to the name of the type, `organic` to false
+    struct Water;
+
+    #[derive(Molecule)]
+    #[molecule(name = "Methane", weight = 16.043, organic = true)]
+    struct Ch4;
+
+    assert_eq!(Water.name(), "Water");
+    assert!(!Water.is_organic());
+    assert_eq!(Ch4.name(), "Methane");
+    assert_eq!(Water.weight() + Ch4.weight(), 34.058);
+}
+
+/// Attribute macros replace the item they are attached to.
+/// `#[call_count]` from `snippets_derive` adds a counter to the function and generates a second function returning the count.
+#[test]
+pub fn attribute_macros() {
+    use snippets_derive::call_count;
+
+    #[call_count]
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    assert_eq!(add_call_count(), 0); // `add_call_count` was generated by the macro
+
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(add(3, 4), 7);
+
+    assert_eq!(add_call_count(), 2);
+}
+
 #[cfg(feature = "python")]
 #[macro_export]
 macro_rules! pyprint {
@@ -1,5 +1,57 @@
 //! Matching is a way to compare a value against a series of patterns and conditionally execute code based on the pattern that matches.
 //! Patterns can be made up of literal values, variable names, wildcards, and many other things.
+//!
+//! https://doc.rust-lang.org/book/ch18-03-pattern-syntax.html
+//!
+//! A `match` has to be exhaustive: every possible value has to match one of the arms.
+//!
+//! ```compile_fail,E0004
+//! let x: Option<i32> = Some(1);
+//!
+//! match x {
+//!     Some(x) if x > 0 => {}
+//!     None => {}
+//! } // <- patterns `Some(i32::MIN..=0_i32)` and `Some(1_i32..)` not covered (guards are not considered)
+//! ```
+
+/// The state of matter of an element. It's `#[non_exhaustive]`: variants may be added in later versions (plasma?)
+/// without breaking other crates, because other crates have to match it with a wildcard arm.
+///
+/// ```compile_fail,E0004
+/// use rust_snippets::matching::State;
+///
+/// fn describe(state: State) -> &'static str {
+///     match state {
+///         State::Solid => "keeps its shape",
+///         State::Liquid => "takes the shape of its container",
+///         State::Gas => "fills its container",
+///     } // <- `_` not covered, even though every variant that exists today is
+/// }
+/// ```
+///
+/// ```
+/// use rust_snippets::matching::State;
+///
+/// fn describe(state: State) -> &'static str {
+///     match state {
+///         State::Solid => "keeps its shape",
+///         State::Liquid => "takes the shape of its container",
+///         State::Gas => "fills its container",
+///         _ => "unknown", // <- for the variants of later versions
+///     }
+/// }
+///
+/// assert_eq!(describe(State::Gas), "fills its container");
+/// ```
+///
+/// Within this crate the attribute has no effect, a match without wildcard compiles (see `non_exhaustive`).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Solid,
+    Liquid,
+    Gas,
+}
 
 /// Enums are a way to define Variants of a type
 /// They are the typical way type to match against
@@ -55,37 +107,310 @@ fn match_result() {
     assert_eq!(y, 1);
 }
 
+/// `lines()` yields `Option<Result<String, Error>>`: there may be no line (`None`), and reading it may fail (`Err`).
+/// `transpose` turns it inside out into `Result<Option<String>, Error>`, so the error can be handled with `?` and the rest is an `Option`.
 #[test]
 fn match_result_option() {
     use std::io::BufRead;
 
-    // this can fail in multiple ways -> Error
-    let file = match std::fs::File::open("does_not_exist.txt") {
-        Ok(file) => file,
+    fn first_line(path: &std::path::Path) -> std::io::Result<Option<String>> {
+        let file = std::fs::File::open(path)?; // <- opening can fail in multiple ways -> Error
+
+        std::io::BufReader::new(file).lines().next().transpose() // <- `Option<Result<..>>` -> `Result<Option<..>>`
+    }
+
+    let file = crate::raii::TempFile::new("first_line.txt"); // <- removes the file when the test ends, even if it panics
+
+    // the file doesn't exist yet -> Err
+    let error = first_line(file.path()).unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+
+    // an empty file has no first line -> Ok(None)
+    std::fs::write(file.path(), "").unwrap();
+
+    assert_eq!(first_line(file.path()).unwrap(), None);
+
+    std::fs::write(file.path(), "iron\ngold\n").unwrap();
+
+    assert_eq!(first_line(file.path()).unwrap(), Some("iron".to_string()));
 
-        Err(_) => {
-            // this can fail in multiple ways -> Error
-            // `unwrap()` is a shortcut for match
-            std::fs::File::create("does_not_exist.txt").unwrap()
+    // all three cases in one `match` on the nested types
+    let line = match first_line(file.path()) {
+        Ok(Some(line)) => line,
+        Ok(None) => "".to_string(),
+        Err(error) => panic!("{error}"),
+    };
+
+    assert_eq!(line, "iron");
+
+    // `transpose` works the other way round too, and `flatten` drops a level of `Option` (or `Result`)
+    let parsed: Option<Result<u8, std::num::ParseIntError>> = Some("26".parse());
+
+    assert_eq!(parsed.clone().transpose(), Ok(Some(26)));
+    assert_eq!(Some(Some(26)).flatten(), Some(26));
+    assert_eq!(parsed.and_then(Result::ok), Some(26)); // <- drops the error
+}
+
+/// A guard (`if` after the pattern) adds a condition to an arm. `|` matches one of several patterns, `..=` a range.
+#[test]
+fn match_guards() {
+    fn classify(number: i32) -> &'static str {
+        match number {
+            n if n < 0 => "negative",
+            0 => "zero",
+            2 | 3 | 5 | 7 => "small prime",
+            1..=9 => "digit",
+            n if n % 2 == 0 => "even",
+            _ => "odd",
         }
+    }
+
+    assert_eq!(classify(-5), "negative");
+    assert_eq!(classify(0), "zero");
+    assert_eq!(classify(2), "small prime");
+    assert_eq!(classify(8), "digit");
+    assert_eq!(classify(26), "even");
+    assert_eq!(classify(79), "odd");
+
+    // a guard applies to all patterns of the arm: `(1 | 2) if ..`, not `1 | (2 if ..)`
+    let isotope = |number: u8, neutrons: u8| match number {
+        1 | 2 if neutrons > number => "heavy isotope",
+        1 | 2 => "light element",
+        _ => "other",
     };
 
-    let line = std::io::BufReader::new(file).lines().nth(0); // `Option<Result<String, Error>>`
+    assert_eq!(isotope(1, 2), "heavy isotope"); // <- tritium
+    assert_eq!(isotope(1, 0), "light element");
+    assert_eq!(isotope(2, 2), "light element");
+}
+
+/// `name @ pattern` binds the value to `name` and tests it against the pattern at the same time.
+#[test]
+fn at_bindings() {
+    struct Element {
+        number: u8,
+        symbol: &'static str,
+    }
+
+    fn period(element: &Element) -> String {
+        match element {
+            Element {
+                number: number @ 1..=2,
+                symbol,
+            } => format!("{symbol} ({number}) is in period 1"),
+            Element {
+                number: number @ 3..=10,
+                ..
+            } => format!("{number} is in period 2"),
+            Element { number, .. } => format!("{number} is in a later period"),
+        }
+    }
 
-    // this can fail in multiple ways -> Error
-    std::fs::remove_file("does_not_exist.txt").unwrap();
+    assert_eq!(
+        period(&Element {
+            number: 2,
+            symbol: "He"
+        }),
+        "He (2) is in period 1"
+    );
+    assert_eq!(
+        period(&Element {
+            number: 6,
+            symbol: "C"
+        }),
+        "6 is in period 2"
+    );
 
-    // the line can either exist or not -> Option
-    let line = match line {
-        Some(line) => line,
-        None => Ok("".to_string()),
+    // with an or-pattern in parentheses
+    let noble = match Some(10) {
+        Some(number @ (2 | 10 | 18)) => format!("{number} is a noble gas"),
+        _ => "not a noble gas".to_string(),
     };
 
-    // the reading of the line can fail in multiple ways -> Result
-    let line = match line {
-        Ok(line) => line,
-        Err(_) => "".to_string(),
+    assert_eq!(noble, "10 is a noble gas");
+}
+
+/// Patterns mirror the way a value is built, so nested structs, tuples, enums and arrays can be taken apart in one pattern.
+/// `..` skips the remaining fields of a struct or elements of a tuple or slice.
+#[test]
+fn destructuring() {
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    enum Shape {
+        Circle { center: Point, radius: i32 },
+        Line(Point, Point),
+    }
+
+    let shapes = [
+        Shape::Circle {
+            center: Point { x: 0, y: 0 },
+            radius: 2,
+        },
+        Shape::Line(Point { x: 1, y: 2 }, Point { x: 1, y: 5 }),
+    ];
+
+    let descriptions: Vec<String> = shapes
+        .iter()
+        .map(|shape| match shape {
+            Shape::Circle {
+                center: Point { x: 0, y: 0 },
+                radius,
+            } => format!("circle of radius {radius} at the origin"),
+            Shape::Circle { radius, .. } => format!("circle of radius {radius}"),
+            Shape::Line(Point { x: x1, y: y1 }, Point { x: x2, y: y2 }) if x1 == x2 => {
+                format!("vertical line of length {}", y2 - y1)
+            }
+            Shape::Line(..) => "line".to_string(),
+        })
+        .collect();
+
+    assert_eq!(
+        descriptions,
+        [
+            "circle of radius 2 at the origin",
+            "vertical line of length 3"
+        ]
+    );
+
+    // tuples and `let`, a `let` pattern has to be irrefutable (always match)
+    let ((a, b), [c, _, e]) = ((1, 2), [3, 4, 5]);
+
+    assert_eq!((a, b, c, e), (1, 2, 3, 5));
+
+    // slices have no fixed length, the patterns say which lengths they match
+    fn describe(elements: &[&str]) -> String {
+        match elements {
+            [] => "none".to_string(),
+            [single] => format!("only {single}"),
+            [first, last] => format!("{first} and {last}"),
+            [first, .., last] => format!("{first} to {last}"),
+        }
+    }
+
+    assert_eq!(describe(&[]), "none");
+    assert_eq!(describe(&["H"]), "only H");
+    assert_eq!(describe(&["H", "He"]), "H and He");
+    assert_eq!(describe(&["H", "He", "Li", "Be"]), "H to Be");
+
+    // `rest @ ..` binds the skipped part as a slice
+    if let [first, rest @ ..] = ["H", "He", "Li"].as_slice() {
+        assert_eq!(*first, "H");
+        assert_eq!(rest, ["He", "Li"]);
+    }
+}
+
+/// Matching a reference binds references (default binding modes, "match ergonomics"): `&Option<String>` with `Some(name)` binds `name: &String`.
+/// Matching a value moves (or copies) out of it, `ref` and `ref mut` bind a reference instead.
+#[test]
+fn ref_bindings() {
+    let name = Some(String::from("iron"));
+
+    let length = match &name {
+        Some(name) => name.len(), // <- `name: &String`
+        None => 0,
+    };
+
+    assert_eq!(length, 4);
+
+    let length = match name {
+        Some(ref name) => name.len(), // <- `ref`: borrows instead of moving the `String` out of `name`
+        None => 0,
+    };
+
+    assert_eq!(length, 4);
+
+    assert!(name.is_some()); // <- still usable, nothing was moved
+
+    let mut element = (String::from("gold"), 79);
+
+    let (ref mut symbol, number) = element; // <- `symbol: &mut String`, `number: i32` (copied)
+
+    symbol.push_str(" (Au)");
+
+    assert_eq!(number, 79);
+    assert_eq!(element.0, "gold (Au)");
+}
+
+/// `if let` matches one pattern, `else` handles the rest. `let else` binds the values of a pattern for the rest of the block
+/// and leaves it (`return`, `break`, `continue`, panic) otherwise. `while let` loops until the pattern doesn't match anymore.
+#[test]
+fn if_let_let_else_while_let() {
+    fn parse(input: &str) -> Option<(&str, u8)> {
+        let Some((symbol, number)) = input.split_once(':') else {
+            return None; // <- the `else` block has to diverge
+        };
+
+        let Ok(number) = number.trim().parse::<u8>() else {
+            return None;
+        };
+
+        Some((symbol.trim(), number)) // <- `symbol` and `number` are in scope after the `let else`
+    }
+
+    assert_eq!(parse("Fe: 26"), Some(("Fe", 26)));
+    assert_eq!(parse("Fe"), None);
+    assert_eq!(parse("Fe: iron"), None);
+
+    // `if let` .. `else if let` .. `else` chains test patterns one after another
+    let describe = |input: &str| {
+        if let Some((symbol, number)) = parse(input) {
+            format!("{symbol} is number {number}")
+        } else if let Ok(number) = input.parse::<u8>() {
+            format!("number {number}")
+        } else {
+            "unknown".to_string()
+        }
+    };
+
+    assert_eq!(describe("Au: 79"), "Au is number 79");
+    assert_eq!(describe("47"), "number 47");
+    assert_eq!(describe("?"), "unknown");
+
+    // `if let .. && condition` (let chains) needs edition 2024, in edition 2021 a tuple or a guard does the same
+    if let (Some((symbol, _)), Some((_, number))) = (parse("Fe: 26"), parse("Au: 79")) {
+        assert_eq!((symbol, number), ("Fe", 79));
+    }
+
+    // `while let` pops until the stack is empty
+    let mut stack = vec!["H", "He", "Li"];
+    let mut popped = Vec::new();
+
+    while let Some(top) = stack.pop() {
+        popped.push(top);
+    }
+
+    assert_eq!(popped, ["Li", "He", "H"]);
+}
+
+/// `matches!(value, pattern)` is a `match` that returns `true` if the pattern (with an optional guard) matches.
+#[test]
+fn matches_macro() {
+    let states = [State::Solid, State::Liquid, State::Gas];
+
+    let fluids = states
+        .iter()
+        .filter(|state| matches!(state, State::Liquid | State::Gas))
+        .count();
+
+    assert_eq!(fluids, 2);
+
+    assert!(matches!(26, 21..=30)); // <- a transition metal of period 4
+    assert!(matches!(Some(26), Some(n) if n % 2 == 0));
+    assert!(!matches!("Fe: 26".split_once(':'), Some((_, "")))); // <- `&str` literals work as patterns too
+}
+
+/// `#[non_exhaustive]` only affects other crates: here `State` can be matched without a wildcard.
+#[test]
+fn non_exhaustive() {
+    let description = match State::Liquid {
+        State::Solid => "solid",
+        State::Liquid => "liquid",
+        State::Gas => "gas",
     };
 
-    assert_eq!(line, "");
+    assert_eq!(description, "liquid");
 }
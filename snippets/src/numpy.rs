@@ -1,30 +1,23 @@
 #![allow(unused)]
 
 use numpy::{ndarray::Array1, IntoPyArray};
-use pyo3::{types::PyDict, Python};
 
+/// The array is passed to Python as a numpy array, the harness converts the resulting numpy array back (`tolist()`).
 #[test]
 pub fn into_pyarray() {
-    pyo3::prepare_freethreaded_python();
+    use crate::harness::python::{compare, run_python_with, run_rust};
 
-    Python::with_gil(|py| {
-        let array = Array1::from_vec(vec![1.0, 2.0, 3.0]);
-        let array = array.into_pyarray(py);
+    let array = Array1::from_vec(vec![1.0, 2.0, 3.0]);
 
-        let locals = PyDict::new(py);
-
-        locals.set_item("array", array).unwrap();
-
-        py.run(
-            r#"
+    let python = run_python_with(
+        r#"
 import numpy as np
-array = np.array([k * 10 for k in array])
+result = np.array([k * 10 for k in array])
         "#,
-            None,
-            Some(locals),
-        )
-        .unwrap();
+        |py, variables| variables.set_item("array", array.clone().into_pyarray(py)),
+    );
+
+    let rust = run_rust(|_| Ok::<_, String>((array * 10.0).to_vec()));
 
-        println!("{:?}", locals.get_item("array").unwrap());
-    });
+    compare(python, rust).assert_equivalent();
 }
@@ -1,6 +1,32 @@
 //! Printing in Rust is primarly done with the `println!` macro.
 //! Alternatively you can use the `print!` macro to print without a newline.
 //! The `dbg!` macro can be used to print the expression inside the macro with its value and location (file:line).
+//! `format!` formats into a `String` instead of printing, with the same format string.
+
+/// A value with a unit. `Display` decides how `{}` prints a type, the precision of the format string (`{:.2}`) is passed on to it.
+///
+/// ```
+/// use rust_snippets::print::Quantity;
+///
+/// let iron = Quantity { value: 55.845, unit: "g/mol" };
+///
+/// assert_eq!(format!("{iron}"), "55.845 g/mol");
+/// assert_eq!(format!("{iron:.1}"), "55.8 g/mol"); // <- rounded to one decimal
+/// assert_eq!(format!("{:?}", iron.value), "55.845"); // <- `{:?}` uses `Debug` instead
+/// ```
+pub struct Quantity {
+    pub value: f64,
+    pub unit: &'static str,
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.precision$} {}", self.value, self.unit),
+            None => write!(f, "{} {}", self.value, self.unit),
+        }
+    }
+}
 
 /// The `println!` macro is a variadic macro. It can take any number of arguments.
 /// The first argument is a format string. The remaining arguments are the values to be formatted.
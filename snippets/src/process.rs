@@ -0,0 +1,347 @@
+//! https://doc.rust-lang.org/std/process/index.html
+//!
+//! `Command` is a builder for a child process: the program, its arguments, environment variables and working directory,
+//! and where its stdin, stdout and stderr go (`Stdio::inherit()`, `Stdio::piped()`, `Stdio::null()`).
+//!
+//! - `status()` runs the program with the streams of the parent and returns its `ExitStatus`
+//! - `output()` runs it and captures stdout and stderr as bytes
+//! - `spawn()` starts it and returns a `Child` at once, to write into its stdin, read its output while it runs or wait with a timeout
+//!
+//! The arguments are passed to the program as they are, there is no shell in between: no globbing, no `$VARIABLES`, no quoting.
+//! A shell has to be started explicitly (`sh -c ".."`), which is what the tests do (they only run on Unix).
+//! The C and C++ harness (`harness/c.rs`) uses this module to run the compiled comparison programs.
+//!
+//! The output is bytes (`Vec<u8>`), a program doesn't have to print UTF-8:
+//!
+//! ```compile_fail,E0308
+//! let output = std::process::Command::new("echo").arg("iron").output().unwrap();
+//!
+//! let stdout: String = output.stdout; // <- expected `String`, found `Vec<u8>`, use `String::from_utf8`
+//! ```
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::functions::Callbacks;
+
+/// Waits for the child to exit, at most for `timeout`. If it's still running then, it's killed and `None` is returned.
+/// `Child` has no `wait` with a timeout, so this polls `try_wait`, which doesn't block.
+///
+/// If the child exits on its own between the last `try_wait` and `kill`, its real exit status is returned (on Unix).
+/// The remaining race: a child that is killed by someone else with SIGKILL at the same moment is reported as timed out,
+/// and on other platforms a child exiting at the timeout is always reported as timed out.
+///
+/// ```
+/// use std::time::Duration;
+/// use rust_snippets::process::wait_timeout;
+///
+/// # if cfg!(unix) {
+/// use std::process::{Command, Stdio};
+///
+/// // `cat` waits for input on its stdin, which is never closed
+/// let mut waiting = Command::new("cat").stdin(Stdio::piped()).spawn().unwrap();
+///
+/// assert!(wait_timeout(&mut waiting, Duration::from_millis(100)).unwrap().is_none());
+///
+/// let mut quick = Command::new("sh").args(["-c", "exit 0"]).spawn().unwrap();
+///
+/// assert!(wait_timeout(&mut quick, Duration::from_secs(10)).unwrap().unwrap().success());
+/// # }
+/// ```
+pub fn wait_timeout(child: &mut Child, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+    let start = Instant::now();
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        if start.elapsed() > timeout {
+            let killed = child.kill();
+
+            // reaps the killed child (also if `kill` failed), otherwise it stays a zombie until the parent exits
+            let status = child.wait()?;
+            killed?;
+
+            // the child may have exited on its own after the last `try_wait`, `kill` succeeds on the zombie
+            return Ok((!was_killed(status)).then_some(status));
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Whether the child was terminated by `Child::kill` (SIGKILL on Unix).
+#[cfg(unix)]
+fn was_killed(status: ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal() == Some(9)
+}
+
+/// Other platforms report no signal, a killed child can't be told apart from one that exited.
+#[cfg(not(unix))]
+fn was_killed(_status: ExitStatus) -> bool {
+    true
+}
+
+/// Runs the command and calls the callbacks with every line of its stdout while it's running, instead of collecting the output at the end.
+/// If a line can't be read (e.g. it isn't UTF-8) the child is killed and waited for before the error is returned.
+///
+/// ```
+/// use rust_snippets::functions::Callbacks;
+/// use rust_snippets::process::stream_lines;
+///
+/// # if cfg!(unix) {
+/// let mut lines = Vec::new();
+/// let mut callbacks = Callbacks::new();
+///
+/// callbacks.register(|line: &String| lines.push(line.clone()));
+///
+/// let mut command = std::process::Command::new("sh");
+/// let status = stream_lines(command.args(["-c", "echo iron; echo gold"]), &mut callbacks).unwrap();
+///
+/// drop(callbacks); // <- ends the borrow of `lines`
+///
+/// assert!(status.success());
+/// assert_eq!(lines, ["iron", "gold"]);
+/// # }
+/// ```
+pub fn stream_lines(
+    command: &mut Command,
+    callbacks: &mut Callbacks<'_, String>,
+) -> std::io::Result<ExitStatus> {
+    let mut child = command.stdout(Stdio::piped()).spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+
+    for line in BufReader::new(stdout).lines() {
+        match line {
+            Ok(line) => callbacks.notify(&line),
+            Err(error) => {
+                // returning with `?` would leave the child running (or a zombie once it exits)
+                let _ = child.kill(); // <- fails if it has already exited, which is fine
+                child.wait()?;
+
+                return Err(error);
+            }
+        }
+    }
+
+    child.wait() // <- after reading to the end: the child closed its stdout, it has exited or is about to
+}
+
+/// `args` and `arg` add arguments, `env` sets an environment variable (the others are inherited unless `env_clear` is called),
+/// `current_dir` sets the working directory of the child.
+#[cfg(unix)]
+#[test]
+fn command_builder() {
+    let directory = crate::raii::TempDir::new("command").unwrap();
+
+    let output = Command::new("sh")
+        .args(["-c", "echo \"$1 is $ELEMENT\"; pwd", "sh", "26"]) // <- `$0` is "sh", `$1` is "26"
+        .env("ELEMENT", "iron")
+        .current_dir(directory.path())
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+
+    assert_eq!(lines.next(), Some("26 is iron"));
+
+    // the temporary directory may be behind a symlink (macOS), so both are resolved before comparing
+    let pwd = std::fs::canonicalize(lines.next().unwrap()).unwrap();
+
+    assert_eq!(pwd, std::fs::canonicalize(directory.path()).unwrap());
+
+    // the argument is passed as it is: one argument with a space and a `$`, no shell expands it
+    let output = Command::new("sh")
+        .args(["-c", "echo \"$1\"", "sh", "$HOME and *"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.stdout, b"$HOME and *\n");
+
+    // `env_clear` starts with an empty environment, without `PATH` the program is given with its full path
+    let output = Command::new("/bin/sh")
+        .args(["-c", "echo \"[$HOME]\""])
+        .env_clear()
+        .output()
+        .unwrap();
+
+    assert_eq!(output.stdout, b"[]\n");
+}
+
+/// `output()` captures stdout and stderr, `status()` lets the child print to the terminal of the parent.
+/// A program that can't be found is an `Err` of `spawn`/`output`/`status`, a program that fails is an `Ok` with an unsuccessful status.
+#[cfg(unix)]
+#[test]
+fn capturing_output() {
+    let output = Command::new("sh")
+        .args(["-c", "echo Fe; echo 'not an element' >&2; exit 1"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.stdout, b"Fe\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "not an element\n");
+    assert!(!output.status.success());
+
+    let status = Command::new("sh")
+        .args(["-c", "exit 0"])
+        .stdout(Stdio::null()) // <- would be inherited
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+
+    let missing = Command::new("this_program_does_not_exist").output();
+
+    assert_eq!(missing.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+/// `Stdio::piped()` for stdin gives the parent a `ChildStdin` to write into. The child reads until stdin is closed,
+/// which happens when the `ChildStdin` is dropped. Writing from a thread avoids a deadlock: if the child's stdout pipe is full
+/// while the parent is still writing, both wait for each other.
+#[cfg(unix)]
+#[test]
+fn piping_stdin() {
+    use std::io::Write;
+
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+
+    let writer = std::thread::spawn(move || {
+        for element in ["H", "He", "Li"] {
+            writeln!(stdin, "{element}").unwrap();
+        }
+    }); // <- `stdin` is dropped at the end of the thread, `cat` sees the end of its input
+
+    let output = child.wait_with_output().unwrap(); // <- reads stdout (and stderr) until the end, then waits
+
+    writer.join().unwrap();
+
+    assert_eq!(output.stdout, b"H\nHe\nLi\n");
+}
+
+/// On Unix a process ends with an exit code (0 to 255) or is killed by a signal, then it has no exit code.
+/// `ExitStatusExt` adds `signal()` to `ExitStatus`.
+#[cfg(unix)]
+#[test]
+fn exit_status_and_signals() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let status = Command::new("sh").args(["-c", "exit 3"]).status().unwrap();
+
+    assert!(!status.success());
+    assert_eq!(status.code(), Some(3));
+    assert_eq!(status.signal(), None);
+
+    let status = Command::new("sh")
+        .args(["-c", "kill -9 $$"]) // <- the shell kills itself with SIGKILL
+        .status()
+        .unwrap();
+
+    assert_eq!(status.code(), None);
+    assert_eq!(status.signal(), Some(9));
+
+    // `kill` sends SIGKILL to a child
+    let mut child = Command::new("cat").stdin(Stdio::piped()).spawn().unwrap(); // <- waits for input
+
+    child.kill().unwrap();
+
+    assert_eq!(child.wait().unwrap().signal(), Some(9));
+}
+
+/// A child that hangs would block `wait` (and the test) forever, `wait_timeout` kills it after a while.
+#[cfg(unix)]
+#[test]
+fn timeouts() {
+    let start = Instant::now();
+
+    let mut child = Command::new("cat").stdin(Stdio::piped()).spawn().unwrap(); // <- waits for input that never comes
+
+    assert_eq!(
+        wait_timeout(&mut child, Duration::from_millis(200)).unwrap(),
+        None
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    let mut child = Command::new("sh").args(["-c", "exit 7"]).spawn().unwrap();
+
+    let status = wait_timeout(&mut child, Duration::from_secs(10)).unwrap();
+
+    assert_eq!(status.and_then(|status| status.code()), Some(7));
+}
+
+/// `first | second` without a shell: the stdout of the first child becomes the stdin of the second (`ChildStdout` converts into `Stdio`).
+/// The data flows directly between the children, the parent only reads the end of the pipeline.
+#[cfg(unix)]
+#[test]
+fn pipeline() {
+    let file = crate::raii::TempFile::new("pipeline.txt");
+
+    std::fs::write(file.path(), "iron\ngold\nsilver\n").unwrap();
+
+    let mut first = Command::new("cat")
+        .arg(file.path())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let second = Command::new("sh")
+        .args(["-c", "while read -r line; do echo \"${#line} $line\"; done"]) // <- prefixes every line with its length
+        .stdin(first.stdout.take().unwrap())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let output = second.wait_with_output().unwrap();
+
+    assert!(first.wait().unwrap().success()); // <- wait for both, or the first one stays a zombie
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"4 iron\n4 gold\n6 silver\n");
+}
+
+/// `stream_lines` passes every line to the callbacks (see `functions.rs`) as soon as it's printed.
+#[cfg(unix)]
+#[test]
+fn streaming_output() {
+    let mut lines = Vec::new();
+    let mut longest = 0;
+
+    let mut callbacks = Callbacks::new();
+
+    callbacks.register(|line: &String| lines.push(line.clone()));
+    callbacks.register(|line| longest = longest.max(line.len()));
+
+    let status = stream_lines(
+        Command::new("sh").args(["-c", "for element in H He Li; do echo $element; done"]),
+        &mut callbacks,
+    )
+    .unwrap();
+
+    drop(callbacks);
+
+    assert!(status.success());
+    assert_eq!(lines, ["H", "He", "Li"]);
+    assert_eq!(longest, 2);
+
+    // a line that isn't UTF-8 is an error, the child (which would keep printing) is killed instead of left running
+    let start = Instant::now();
+    let mut callbacks = Callbacks::new();
+
+    let error = stream_lines(
+        Command::new("sh").args(["-c", "printf '\\377\\n'; exec sleep 60"]),
+        &mut callbacks,
+    )
+    .unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
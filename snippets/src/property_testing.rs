@@ -0,0 +1,117 @@
+//! https://proptest-rs.github.io/proptest/intro.html
+//!
+//! Example based tests assert a single hand-picked value: `add(1, 2) == 3`.
+//! Property based tests assert that a property holds for every input: `add(a, b) == add(b, a)`.
+//! The inputs are generated randomly from a `Strategy` and the test runner checks the property for many of them (256 by default).
+//! If the property fails the runner "shrinks" the failing input to the simplest value that still fails.
+//!
+//! Properties find the inputs nobody thought of: the overflow at the boundary, the negative number, the empty vector.
+//! The lessons apply properties to their own snippets with [`check`] (see `traits.rs`, `errror_handling.rs`, `casting.rs` and `macros.rs`).
+
+use proptest::strategy::Strategy;
+use proptest::test_runner::{TestCaseError, TestRunner};
+
+/// Checks that `property` holds for the values generated by `strategy` (256 cases with the default runner)
+/// and panics with the shrunk counterexample if it doesn't.
+/// `prop_assert*!` return the error instead of panicking, so the runner can shrink the input.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use rust_snippets::property_testing::check;
+///
+/// // `u16` can hold the sum of any two `u8`, the addition is commutative
+/// check(&(any::<u8>(), any::<u8>()), |(a, b)| {
+///     prop_assert_eq!(a as u16 + b as u16, b as u16 + a as u16);
+///     Ok(())
+/// });
+/// ```
+///
+/// A property that doesn't hold fails with the minimal input, here exactly `100`:
+///
+/// ```should_panic
+/// use proptest::prelude::*;
+/// use rust_snippets::property_testing::check;
+///
+/// check(&any::<u32>(), |x| {
+///     prop_assert!(x < 100);
+///     Ok(())
+/// });
+/// ```
+#[track_caller]
+pub fn check<S: Strategy>(strategy: &S, property: impl Fn(S::Value) -> Result<(), TestCaseError>) {
+    if let Err(error) = TestRunner::default().run(strategy, property) {
+        panic!("{error}");
+    }
+}
+
+/// A `Strategy` describes how to generate values of a type.
+/// `any::<T>()` generates any value of `T`, ranges generate values inside the range.
+/// Strategies can be combined with tuples and transformed with `prop_map`.
+/// The `TestRunner` draws values from the strategy and calls the closure for each of them.
+#[test]
+fn strategies() {
+    use proptest::prelude::*;
+    use proptest::test_runner::TestRunner;
+
+    let mut runner = TestRunner::default();
+
+    let even = (0u32..1000).prop_map(|x| x * 2); // every generated value is mapped to an even number
+
+    runner
+        .run(&even, |x| {
+            prop_assert_eq!(x % 2, 0); // `prop_assert*!` return an error instead of panicking so the runner can shrink
+            prop_assert!(x < 2000);
+            Ok(())
+        })
+        .unwrap();
+
+    runner
+        .run(&(any::<u8>(), any::<u8>()), |(a, b)| {
+            let sum = a as u16 + b as u16; // `u8 + u8` can overflow, `u16` can hold the sum of any two `u8`
+
+            prop_assert_eq!(sum, b as u16 + a as u16);
+            Ok(())
+        })
+        .unwrap();
+}
+
+/// A failing property is reported with the input that made it fail.
+/// Randomly generated inputs are usually large and noisy (e.g. 3_184_210_311), which makes it hard to see why the property failed.
+/// Shrinking simplifies the failing input step by step and keeps the simplification as long as the property still fails.
+/// For integers this is a binary search towards zero, for collections it removes elements and shrinks the remaining ones.
+/// The result is a minimal counterexample at the boundary of the bug.
+#[test]
+fn shrinking() {
+    use proptest::prelude::*;
+    use proptest::test_runner::{Config, RngAlgorithm, TestError, TestRng, TestRunner};
+
+    // a deterministic runner always generates the same inputs and does not store failures on disk
+    let config = Config {
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let rng = TestRng::deterministic_rng(RngAlgorithm::default());
+    let mut runner = TestRunner::new_with_rng(config, rng);
+
+    // deliberately wrong property: "every u32 is smaller than 100"
+    let result = runner.run(&any::<u32>(), |x| {
+        prop_assert!(x < 100);
+        Ok(())
+    });
+
+    match result {
+        Err(TestError::Fail(_reason, minimal)) => assert_eq!(minimal, 100), // shrunk from a large random number to exactly the boundary
+        _ => panic!("the property should fail"),
+    }
+
+    // deliberately wrong property: "a vector never contains a value above 10"
+    let result = runner.run(&prop::collection::vec(0u8..100, 0..50), |v| {
+        prop_assert!(v.iter().all(|&x| x <= 10));
+        Ok(())
+    });
+
+    match result {
+        Err(TestError::Fail(_reason, minimal)) => assert_eq!(minimal, vec![11]), // all other elements are removed, the culprit is shrunk to the boundary
+        _ => panic!("the property should fail"),
+    }
+}
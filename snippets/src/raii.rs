@@ -0,0 +1,368 @@
+//! https://doc.rust-lang.org/book/ch15-03-drop.html
+//! https://doc.rust-lang.org/reference/destructors.html
+//!
+//! RAII (Resource Acquisition Is Initialization): a value owns a resource (memory, a file, a lock, ..) and releases it when it goes out of scope.
+//! The compiler inserts the call to the destructor (`Drop::drop`) where the owner's scope ends, also when the scope is left by `return`, `?` or a panic.
+//! A guard type (`MutexGuard`, `File`, `TempFile`) can't forget its cleanup, because nobody has to remember to call it.
+//!
+//! `drop` can't be called directly, a value could be used (and dropped again) afterwards:
+//!
+//! ```compile_fail,E0040
+//! struct Guard;
+//!
+//! impl Drop for Guard {
+//!     fn drop(&mut self) {}
+//! }
+//!
+//! let guard = Guard;
+//!
+//! guard.drop(); // <- explicit use of destructor method, use `std::mem::drop(guard)` instead
+//! ```
+//!
+//! `std::mem::drop` takes the value by value, so it can't be used afterwards:
+//!
+//! ```compile_fail,E0382
+//! let elements = vec!["iron", "gold"];
+//!
+//! drop(elements); // <- value moved here
+//!
+//! assert_eq!(elements.len(), 2); // <- value borrowed here after move
+//! ```
+//!
+//! A type with a destructor can't be `Copy`, a copy would be dropped twice:
+//!
+//! ```compile_fail,E0184
+//! #[derive(Clone, Copy)]
+//! struct Guard;
+//!
+//! impl Drop for Guard {
+//!     fn drop(&mut self) {}
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A path in the temporary directory whose file is removed when the guard is dropped, also if the test that uses it panics.
+/// Every guard gets its own path (process id and a counter), so tests running in parallel don't share files.
+/// The file isn't created, that's up to the lesson.
+///
+/// ```
+/// use rust_snippets::raii::TempFile;
+///
+/// let path = {
+///     let file = TempFile::new("iron.txt");
+///
+///     std::fs::write(file.path(), "Fe").unwrap();
+///
+///     assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "Fe");
+///     assert_ne!(file.path(), TempFile::new("iron.txt").path()); // <- a different file
+///
+///     file.path().to_path_buf()
+/// }; // <- `file` is dropped and removes the file
+///
+/// assert!(!path.exists());
+/// ```
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    pub fn new(name: &str) -> Self {
+        Self {
+            path: unique_path(name),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // the file may not exist (never created or already removed) and `drop` can't return an error, so errors are ignored
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// A directory in the temporary directory that is removed with everything in it when the guard is dropped.
+/// Like `TempFile` every guard gets its own path, so a test can work inside it without touching other tests' files.
+///
+/// ```
+/// use rust_snippets::raii::TempDir;
+///
+/// let directory = TempDir::new("elements").unwrap();
+/// let path = directory.path().to_path_buf();
+///
+/// std::fs::create_dir(path.join("metals")).unwrap();
+/// std::fs::write(path.join("metals").join("iron.txt"), "Fe").unwrap();
+///
+/// drop(directory);
+///
+/// assert!(!path.exists());
+/// ```
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates the directory.
+    pub fn new(name: &str) -> std::io::Result<Self> {
+        let path = unique_path(name);
+
+        std::fs::create_dir(&path)?;
+
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// `rust_snippets_{process id}_{count}_{name}` in the temporary directory, unique within the process (and between processes).
+fn unique_path(name: &str) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+
+    std::env::temp_dir().join(format!(
+        "rust_snippets_{}_{count}_{name}",
+        std::process::id()
+    ))
+}
+
+/// Records its name into a shared log when it's dropped, to make the drop order visible.
+#[cfg(test)]
+struct Noisy<'a> {
+    name: &'static str,
+    log: &'a std::cell::RefCell<Vec<&'static str>>,
+}
+
+#[cfg(test)]
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.name);
+    }
+}
+
+/// Local variables are dropped in the reverse order of their declaration, at the end of their scope.
+/// Later values may borrow from earlier ones, so the later ones have to go first.
+#[test]
+fn drop_order_locals() {
+    let log = std::cell::RefCell::new(Vec::new());
+    let noisy = |name| Noisy { name, log: &log };
+
+    {
+        let _iron = noisy("iron");
+        let _gold = noisy("gold");
+
+        let _silver = noisy("silver");
+        let _silver = noisy("shadowed silver"); // <- shadowing doesn't drop the first `_silver`, it lives until the end of the scope
+
+        assert!(log.borrow().is_empty());
+    }
+
+    assert_eq!(*log.borrow(), ["shadowed silver", "silver", "gold", "iron"]);
+
+    log.borrow_mut().clear();
+
+    {
+        let iron = noisy("iron");
+        let _gold = noisy("gold");
+
+        drop(iron); // <- `std::mem::drop` is an empty function, it takes ownership and the value is dropped at its end
+
+        assert_eq!(*log.borrow(), ["iron"]);
+    }
+
+    assert_eq!(*log.borrow(), ["iron", "gold"]);
+}
+
+/// The fields of a struct are dropped in the order of their declaration (unlike locals), after the `drop` of the struct itself.
+/// Elements of tuples, arrays and `Vec`s are dropped in order too.
+#[test]
+fn drop_order_fields() {
+    let log = std::cell::RefCell::new(Vec::new());
+    let noisy = |name| Noisy { name, log: &log };
+
+    struct Alloy<'a> {
+        _first: Noisy<'a>,
+        _second: Noisy<'a>,
+        log: &'a std::cell::RefCell<Vec<&'static str>>,
+    }
+
+    impl Drop for Alloy<'_> {
+        fn drop(&mut self) {
+            self.log.borrow_mut().push("alloy"); // <- the fields are still valid here
+        }
+    }
+
+    drop(Alloy {
+        _first: noisy("copper"),
+        _second: noisy("zinc"),
+        log: &log,
+    });
+
+    assert_eq!(*log.borrow(), ["alloy", "copper", "zinc"]);
+
+    log.borrow_mut().clear();
+
+    drop((
+        noisy("a"),
+        [noisy("b"), noisy("c")],
+        vec![noisy("d"), noisy("e")],
+    ));
+
+    assert_eq!(*log.borrow(), ["a", "b", "c", "d", "e"]);
+}
+
+/// Temporaries (values that aren't bound to a variable) are dropped at the end of the statement.
+/// `let _ = ..` doesn't bind, so the value is dropped at once, `let _name = ..` binds and lives until the end of the scope.
+/// A temporary in the scrutinee of a `match` lives until the end of the `match`, which matters for lock guards.
+#[test]
+fn drop_order_temporaries() {
+    let log = std::cell::RefCell::new(Vec::new());
+    let noisy = |name| Noisy { name, log: &log };
+
+    let name = noisy("temporary").name; // <- the `Noisy` is dropped at the `;`, the `&'static str` is copied out
+
+    assert_eq!(*log.borrow(), [name]);
+
+    log.borrow_mut().clear();
+
+    let _ = noisy("wildcard"); // <- dropped here
+    let _bound = noisy("bound"); // <- dropped at the end of the test
+
+    assert_eq!(*log.borrow(), ["wildcard"]);
+
+    log.borrow_mut().clear();
+
+    match noisy("scrutinee").name {
+        "scrutinee" => log.borrow_mut().push("arm"),
+        _ => unreachable!(),
+    } // <- the temporary is dropped here, after the arm
+
+    assert_eq!(*log.borrow(), ["arm", "scrutinee"]);
+
+    let mutex = std::sync::Mutex::new(1);
+
+    // `match *mutex.lock().unwrap() { 1 => *mutex.lock().unwrap() += 1, .. }` would hold the lock in the arm and lock again -> deadlock
+    // the temporaries of an `if` condition are dropped before the block runs
+    if *mutex.lock().unwrap() == 1 {
+        *mutex.lock().unwrap() += 1;
+    }
+
+    assert_eq!(*mutex.lock().unwrap(), 2);
+}
+
+/// `std::mem::forget` takes ownership without running the destructor. It's safe: leaking is not undefined behaviour
+/// (an `Rc` cycle leaks too), so code must not rely on a destructor running for memory safety.
+/// It's used to hand a resource over to something else, e.g. a file descriptor to C (`into_raw_fd` does that).
+#[test]
+fn mem_forget() {
+    let log = std::cell::RefCell::new(Vec::new());
+
+    std::mem::forget(Noisy {
+        name: "forgotten",
+        log: &log,
+    });
+
+    assert!(log.borrow().is_empty()); // <- never dropped
+
+    let file = TempFile::new("forgotten.txt");
+    let path = file.path().to_path_buf();
+
+    std::fs::write(&path, "leaked").unwrap();
+
+    std::mem::forget(file); // <- the guard doesn't remove the file
+
+    assert!(path.exists());
+
+    std::fs::remove_file(path).unwrap(); // <- the cleanup is manual again
+}
+
+/// `ManuallyDrop<T>` is a wrapper that never drops its value, unless `ManuallyDrop::drop` (unsafe) is called.
+/// Unlike `mem::forget` the value can still be used. It's used to control the drop order of fields
+/// or to keep a value alive that is freed by someone else.
+#[test]
+fn manually_drop() {
+    use std::mem::ManuallyDrop;
+
+    let log = std::cell::RefCell::new(Vec::new());
+    let noisy = |name| Noisy { name, log: &log };
+
+    struct Connection<'a> {
+        _socket: Noisy<'a>,
+        session: ManuallyDrop<Noisy<'a>>, // <- has to be closed before the socket, but is declared after it
+    }
+
+    impl Drop for Connection<'_> {
+        fn drop(&mut self) {
+            // SAFETY: `session` is dropped only here and never used afterwards
+            unsafe { ManuallyDrop::drop(&mut self.session) };
+        } // <- then the fields: `_socket` is dropped, `session` is skipped (`ManuallyDrop` has no drop glue)
+    }
+
+    drop(Connection {
+        _socket: noisy("socket"),
+        session: ManuallyDrop::new(noisy("session")),
+    });
+
+    assert_eq!(*log.borrow(), ["session", "socket"]);
+
+    log.borrow_mut().clear();
+
+    {
+        let kept = ManuallyDrop::new(noisy("kept"));
+
+        assert_eq!(kept.name, "kept"); // <- derefs to the value
+    } // <- the wrapper goes out of scope, the value is not dropped
+
+    assert!(log.borrow().is_empty());
+}
+
+/// The guard removes its file even if the test panics after creating it: the panic unwinds the stack and drops every local on the way.
+#[test]
+fn temp_file_guard() {
+    let path = std::sync::Mutex::new(PathBuf::new());
+
+    let result = std::panic::catch_unwind(|| {
+        let file = TempFile::new("panic.txt");
+
+        std::fs::write(file.path(), "Au").unwrap();
+        *path.lock().unwrap() = file.path().to_path_buf();
+
+        panic!("something went wrong"); // <- `file` is dropped while unwinding
+    });
+
+    assert!(result.is_err());
+
+    let path = path.into_inner().unwrap();
+
+    assert!(path.starts_with(std::env::temp_dir()));
+    assert!(!path.exists());
+
+    // every guard has its own path, even with the same name in parallel threads
+    let paths: Vec<PathBuf> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| TempFile::new("parallel.txt").path().to_path_buf()))
+            .collect();
+
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect()
+    });
+
+    let unique: std::collections::HashSet<&PathBuf> = paths.iter().collect();
+
+    assert_eq!(unique.len(), paths.len());
+}
@@ -1,5 +1,26 @@
 //! Rust's entire borrowing model enforces one simple requirement: the contents of a memory location can only be mutated if there is only one pointer through which that location can be accessed.
 //! This model enables the compiler to perform aggressive optimisations
+//!
+//! The borrow checker rejects programs that mutate a value while a reference to it is still in use.
+//! What happens when these rules are bypassed with raw pointers is shown in `ub_lab.rs`.
+//!
+//! ```compile_fail,E0506
+//! let mut x = Box::new(1);
+//! let y: &i32 = &x; // <- borrowed here
+//!
+//! *x += 1; // <- can't assign to `*x` because it is borrowed
+//!
+//! assert_eq!(*y, 2); // <- borrow later used here
+//! ```
+//!
+//! ```compile_fail,E0502
+//! let mut v: Vec<u32> = vec![1, 2, 3];
+//! let r1 = &v[2]; // <- immutable borrow occurs here
+//!
+//! v.pop(); // <- cannot borrow `v` as mutable because it is also borrowed as immutable
+//!
+//! dbg!(r1); // <- immutable borrow later used here
+//! ```
 
 #[test]
 pub fn reference() {
@@ -17,19 +38,6 @@ pub fn reference() {
     println!("{:?}", x);
 }
 
-#[test]
-#[you_can::turn_off_the_borrow_checker]
-pub fn borrow_checker_fine() {
-    let mut x = Box::new(1);
-    let y: &i32 = &x; // <- borrowed here
-
-    *x += 1; // can't assign to x because it is still borrowed
-
-    println!("{x}");
-
-    assert_eq!(*y, 2); // <- borrow used
-}
-
 /// Arc<Mutex<T>> is a thread-safe reference-counted pointer to a mutable value protected by a mutex.
 /// This is useful when you want to share a mutable value between multiple threads.
 #[test]
@@ -63,48 +71,3 @@ fn borrow_checker_arc_mutex() {
 
     assert_eq!(*x.lock().unwrap(), 5); // assuming the threads have finished
 }
-
-#[test]
-#[you_can::turn_off_the_borrow_checker]
-fn borrow_checker_undefined() {
-    let mut v: Vec<u32> = vec![1, 2, 3];
-    let r1 = &v[2];
-
-    dbg!(r1, v.get(2)); // 3 and 3 ✓
-
-    v.pop(); // Error: Cannot borrow `v` as mutable because it is also borrowed as immutable
-
-    let mut v: Vec<i32> = v.into_iter().map(|x| x as i32).collect();
-
-    v.push(-10);
-
-    assert_eq!(v, vec![1, 2, -10]);
-
-    dbg!(r1, v.get(2)); // 4294967286 and -10 X ?
-
-    v.pop();
-
-    assert_eq!(v, vec![1, 2]);
-
-    dbg!(r1, v.get(2)); // 4294967286 and None X ?
-
-    let mut v: Vec<String> = v.into_iter().map(|x| x.to_string()).collect();
-
-    v.push("hello".to_string());
-
-    let r2 = &v[2];
-
-    assert_eq!(v, vec!["1", "2", "hello"]);
-
-    dbg!(r1, v.get(2)); // 4294967286 and Some("hello") X ?
-
-    v.pop();
-
-    let mut v: Vec<i32> = v.into_iter().map(|x| x.parse::<i32>().unwrap()).collect();
-
-    v.push(3);
-
-    assert_eq!(v, vec![1, 2, 3]);
-
-    dbg!(r1, r2, v.get(2)); // 4294967286 and Some("hello") and Some(3) X ?
-}
@@ -3,6 +3,30 @@
 //! Rusts ownership system allows for memory safety without garbage collection.
 //! In C you can write invalid code and have it compile without any warnings or errors.
 
+#[cfg(all(test, feature = "cc"))]
+use crate::harness::c::Program;
+
+/// The Rust version of `getArray` from `dangling_pointer.c`: the array is a constant, so the reference is valid for the whole program.
+///
+/// ```
+/// assert_eq!(rust_snippets::safety::get_array(), &[1, 2, 3]);
+/// ```
+///
+/// Returning a reference to a local array like the C version doesn't compile:
+///
+/// ```compile_fail,E0515
+/// fn get_array() -> &'static [i32; 3] {
+///     let array = [1, 2, 3];
+///
+///     &array // <- returns a reference to data owned by the current function
+/// }
+/// ```
+pub fn get_array() -> &'static [i32; 3] {
+    const ARRAY: [i32; 3] = [1, 2, 3];
+
+    &ARRAY
+}
+
 /// The C compiler does not check for uninitialized variables and lets you use them. This can lead to undefined behavior.
 /// Rust will not compile if you try to use an uninitialized variable (and also has useful warnings and potential fixes for the unused variables).
 #[cfg(feature = "cc")]
@@ -22,12 +46,15 @@ fn test_c_uninitialized() {
 
     */
 
-    cc::Build::new()
-        .file("../c/src/uninitialized.c")
-        .target("x86_64-pc-windows-msvc")
-        .host("x86_64-pc-windows-msvc")
-        .opt_level(0)
-        .compile("unsafe");
+    let c = Program::file("uninitialized.c")
+        .documented_in(include_str!("safety.rs"))
+        .compile()
+        .unwrap(); // <- compiles, with `-Wall` at most a warning "'num' is used uninitialized"
+
+    let output = c.run();
+
+    assert_eq!(output.code, Some(0));
+    assert!(output.stdout.starts_with("The value of num is: ")); // <- followed by whatever was in that memory
 
     let num: i32; // uninitialized variable -> You can declare variables without initializing them in Rust.
 
@@ -48,50 +75,40 @@ fn test_c_uninitialized() {
 fn test_c_dangling_pointer() {
     /* dangling_pointer.c
 
-     #include <stdio.h>
-
+    #include <stdio.h>
 
     int* getArray() {
-
         int array[3] = {1, 2, 3};
 
         return array;
-
     }
 
-
     void printArray(int* arr) {
-
         for (int i = 0; i < 3; i++) {
-
             printf("%d ", arr[i]);
-
         }
 
         printf("\n");
-
     }
 
-
     int main() {
-
         int* danglingPointer = getArray();
 
         printArray(danglingPointer); // Using dangling pointer
 
-
         return 0;
-
     }
 
     */
 
-    cc::Build::new()
-        .file("../c/src/dangling_pointer.c")
-        .target("x86_64-pc-windows-msvc")
-        .host("x86_64-pc-windows-msvc")
-        .opt_level(0)
-        .compile("unsafe");
+    let c = Program::file("dangling_pointer.c")
+        .documented_in(include_str!("safety.rs"))
+        .compile()
+        .unwrap(); // <- compiles, only with a warning
+
+    assert!(c.warnings.contains("local variable")); // <- "function returns address of local variable"
+
+    let output = c.run();
 
     // The following function is in no way possible to compile in Rust.
     // The Ownership system completely prevents this error from happening.
@@ -115,4 +132,20 @@ fn test_c_dangling_pointer() {
     }
 
     assert!(get_array() == &1);
+
+    fn print_array(array: &[i32]) -> String {
+        array
+            .iter()
+            .map(|number| format!("{number} "))
+            .collect::<String>()
+            + "\n"
+    }
+
+    let rust = print_array(&[*get_array(), 2, 3]);
+
+    assert_eq!(rust, "1 2 3 \n");
+
+    // what the C program does is undefined: GCC returns NULL instead of the address (the program crashes),
+    // other compilers print whatever is left on the stack, which can even be `1 2 3`, so it's only printed
+    println!("dangling_pointer.c: {output}");
 }
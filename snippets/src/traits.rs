@@ -3,7 +3,36 @@
 //! Traits are a way to share functionality between different types.
 //! They are implemented with the `impl` keyword.
 //! They can be implemented for any type.
-//! There are certain rules that have to be followed when implementing traits for types outside of the crate -> Orphan Rules
+//! There are certain rules that have to be followed when implementing traits for types outside of the crate -> Orphan Rules (see generics.rs)
+
+/// A point in 2D space. `Add` is implemented, so points can be added with `+`.
+///
+/// ```
+/// use rust_snippets::traits::Point;
+///
+/// let a = Point(1.0, 2.0);
+/// let b = Point(3.0, 4.0);
+///
+/// assert_eq!(a + b, Point(4.0, 6.0)); // `assert_eq!` needs `PartialEq` and `Debug` (derived)
+/// ```
+///
+/// Operators are traits: without an `impl Add` the `+` doesn't compile:
+///
+/// ```compile_fail,E0369
+/// struct Meters(f32);
+///
+/// let distance = Meters(1.0) + Meters(2.0); // <- cannot add `Meters` to `Meters`
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point(pub f32, pub f32);
+
+impl std::ops::Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point(self.0 + other.0, self.1 + other.1)
+    }
+}
 
 #[test]
 fn traits() {
@@ -55,12 +84,18 @@ fn derive() {
     use core::cmp::PartialEq;
     use std::fmt::Debug;
 
-    #[derive(Debug, PartialEq)] // `Add` does not have a derive macro implemented for it
+    #[derive(Debug, PartialEq)] // `Add` does not have a derive macro in the standard library
     struct Point(f32, f32); // `f32` implements `PartialEq` and `Debug`
 
     let p1 = Point(1.0, 2.0);
 
     assert_eq!(p1, Point(1.0, 2.0));
+
+    // Derive macros can be written for any trait (see macros.rs) -> `snippets_derive` provides one for `Add`
+    #[derive(Debug, PartialEq, snippets_derive::Add)]
+    struct Point2(f32, f32); // `f32` implements `Add`
+
+    assert_eq!(Point2(1.0, 2.0) + Point2(3.0, 4.0), Point2(4.0, 6.0));
 }
 
 /// Custom traits are defined with the `trait` keyword.
@@ -126,7 +161,9 @@ fn custom_traits() {
 // Traits are often used as marker traits to denote certain properties of a type.
 #[test]
 fn marker_traits() {
-    trait Metallic {}
+    use std::any::{Any, TypeId}; // standard library trait for type checking
+
+    trait Metallic: Any {} // `Any` as supertrait -> every `Metallic` type can be identified at runtime
 
     struct Iron;
 
@@ -143,35 +180,29 @@ fn marker_traits() {
     list_of_metals.push(Box::new(Iron));
     list_of_metals.push(Box::new(Gold));
 
-    use std::any::Any; // standard library trait for type checking
     use std::ops::Deref; // standard library trait for dereferencing
 
-    assert_eq!(
-        list_of_metals[0].deref().type_id(),
-        std::any::TypeId::of::<dyn Metallic>()
-    );
+    // `type_id()` is dispatched through the vtable of `dyn Metallic` to the concrete type
+    assert_eq!(list_of_metals[0].deref().type_id(), TypeId::of::<Iron>());
+    assert_eq!(list_of_metals[1].deref().type_id(), TypeId::of::<Gold>());
+
+    // More on trait objects, `Any` and the pitfalls of `type_id()` in dyn_dispatch.rs
 }
 
 #[test]
 fn traits_and_generics() {
+    // `String` does not implement `Add<String>` -> the names are combined with `concat` instead
+    #[derive(snippets_derive::Add)]
     struct Animal {
+        #[add(with = "concat")]
         name: String,
         age: u32,
     }
 
-    impl Add for Animal {
-        type Output = Self;
-
-        fn add(self, other: Self) -> Self {
-            Self {
-                name: format!("{}{}", self.name, other.name),
-                age: self.age + other.age,
-            }
-        }
+    fn concat(a: String, b: String) -> String {
+        format!("{}{}", a, b)
     }
 
-    use std::ops::Add;
-
     fn add<T: std::ops::Add<Output = T>>(a: T, b: T) -> T {
         a + b
     }
@@ -191,3 +222,24 @@ fn traits_and_generics() {
     assert_eq!(cat_turtle.name, "CatTurtle");
     assert_eq!(cat_turtle.age, 121);
 }
+
+/// A trait implementation has to follow the rules of the trait, for `Add` the rules of addition (checked for `Point` above).
+#[test]
+fn add_properties() {
+    use crate::property_testing::check;
+    use proptest::prelude::*;
+
+    // Floating point addition is commutative but NOT associative because every intermediate result is rounded
+    assert_ne!((1e8f32 + -1e8) + 1.0, 1e8f32 + (-1e8 + 1.0)); // 1.0 != 0.0
+
+    // Whole numbers up to 2^24 are exactly representable as `f32` -> their sums are exact and associative
+    let coordinate = (-1_000_000i32..1_000_000).prop_map(|x| x as f32);
+    let point = (coordinate.clone(), coordinate).prop_map(|(x, y)| Point(x, y));
+
+    // property: `a + b == b + a` (commutative) and `(a + b) + c == a + (b + c)` (associative)
+    check(&(point.clone(), point.clone(), point), |(a, b, c)| {
+        prop_assert_eq!(a + b, b + a);
+        prop_assert_eq!((a + b) + c, a + (b + c));
+        Ok(())
+    });
+}
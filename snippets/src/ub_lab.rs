@@ -0,0 +1,242 @@
+//! https://github.com/rust-lang/miri
+//!
+//! Undefined behaviour (UB) can not be observed by running a program: the compiler assumes it never happens, so the result is whatever the optimiser made of it.
+//! A UB program can print the "right" value, a garbage value or crash, and the output can change with the platform, the optimisation level or the next compiler release.
+//! Asserting on the printed value of a UB program therefore tests nothing.
+//!
+//! Miri is an interpreter for Rust's mid-level IR that checks every memory access against the rules of the language (bounds, liveness, initialisation and aliasing).
+//! It reports UB deterministically, with the kind of violation and the line where it happened.
+//! Install it with `rustup +nightly component add miri`.
+//!
+//! The lab reproduces the borrow checker violations from `references.rs` with explicit `unsafe` raw pointers.
+//! The scenarios are standalone programs in `snippets/ub_lab/`. They are never executed natively, only under Miri.
+//! If Miri is not installed the tests skip (with a note on stderr) instead of failing.
+
+#[cfg(test)]
+use std::path::PathBuf;
+#[cfg(test)]
+use std::process::Command;
+
+/// The kind of undefined behaviour reported by Miri, parsed from its output.
+///
+/// ```
+/// use rust_snippets::ub_lab::Diagnostic;
+///
+/// let stderr = "error: Undefined Behavior: memory access failed: alloc1 has been freed, so this pointer is dangling";
+///
+/// assert_eq!(Diagnostic::from_miri_output(false, stderr), Diagnostic::UseAfterFree);
+/// assert_eq!(Diagnostic::from_miri_output(true, ""), Diagnostic::Defined); // <- Miri ran the program to the end
+/// ```
+#[derive(Debug, PartialEq)]
+pub enum Diagnostic {
+    /// The program ran to completion without undefined behaviour.
+    Defined,
+    /// A reference or pointer was used after it was invalidated by another access (Stacked Borrows / Tree Borrows).
+    AliasingViolation,
+    /// Memory was accessed after its allocation was freed.
+    UseAfterFree,
+    /// Memory was accessed outside of its allocation.
+    OutOfBounds,
+    /// Uninitialized memory was read.
+    Uninitialized,
+    /// Anything else, with Miri's error message.
+    Other(String),
+}
+
+impl Diagnostic {
+    /// `success` is whether `cargo miri run` exited successfully, `stderr` what it printed.
+    pub fn from_miri_output(success: bool, stderr: &str) -> Self {
+        if success {
+            return Diagnostic::Defined;
+        }
+
+        let Some(error) = stderr
+            .lines()
+            .find(|line| line.contains("Undefined Behavior"))
+        else {
+            return Diagnostic::Other(stderr.to_string());
+        };
+
+        if stderr.contains("borrow stack")
+            || stderr.contains("Stacked Borrows")
+            || stderr.contains("Tree Borrows")
+        {
+            Diagnostic::AliasingViolation
+        } else if error.contains("freed") {
+            Diagnostic::UseAfterFree
+        } else if error.contains("out-of-bounds")
+            || error.contains("beyond the end of the allocation")
+        {
+            Diagnostic::OutOfBounds
+        } else if error.contains("uninitialized") {
+            Diagnostic::Uninitialized
+        } else {
+            Diagnostic::Other(error.to_string())
+        }
+    }
+}
+
+/// `cargo miri` if it is installed, either for the active toolchain or for nightly.
+#[cfg(test)]
+fn miri() -> Option<Command> {
+    if cfg!(miri) {
+        return None; // <- Miri can't spawn processes, the lab itself doesn't run under Miri
+    }
+
+    let candidates: [&[&str]; 2] = [&["miri"], &["+nightly", "miri"]];
+
+    candidates.into_iter().find_map(|args| {
+        let installed = Command::new("cargo")
+            .args(args)
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success());
+
+        installed.then(|| {
+            let mut command = Command::new("cargo");
+            command.args(args);
+            command
+        })
+    })
+}
+
+/// Runs `snippets/ub_lab/{name}.rs` as the `main.rs` of a throwaway crate under Miri.
+/// Returns `None` if Miri is not installed.
+#[cfg(test)]
+fn run_under_miri(name: &str) -> Option<Diagnostic> {
+    let Some(mut miri) = miri() else {
+        eprintln!("Miri is not installed, skipping `{name}` (rustup +nightly component add miri)");
+        return None;
+    };
+
+    let source = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("ub_lab")
+        .join(format!("{name}.rs"));
+
+    let krate = std::env::temp_dir().join(format!("ub_lab_{name}_{}", std::process::id()));
+    std::fs::create_dir_all(krate.join("src")).unwrap();
+
+    // the empty `[workspace]` keeps cargo from looking for a parent workspace
+    let manifest = format!(
+        "[package]\nname = \"{name}\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[workspace]\n"
+    );
+    std::fs::write(krate.join("Cargo.toml"), manifest).unwrap();
+    std::fs::copy(&source, krate.join("src").join("main.rs")).unwrap();
+
+    // an inherited `CARGO_TARGET_DIR` would be shared between runs and point cargo-miri to the deleted crate of an earlier run
+    let output = miri
+        .args(["run", "--quiet"])
+        .current_dir(&krate)
+        .env("CARGO_TARGET_DIR", krate.join("target"))
+        .output()
+        .unwrap();
+
+    std::fs::remove_dir_all(&krate).ok();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    Some(Diagnostic::from_miri_output(
+        output.status.success(),
+        &stderr,
+    ))
+}
+
+#[cfg(test)]
+fn assert_miri_reports(name: &str, expected: Diagnostic) {
+    if let Some(diagnostic) = run_under_miri(name) {
+        assert_eq!(diagnostic, expected, "unexpected Miri result for `{name}`");
+    }
+}
+
+/// A shared reference promises that the value behind it does not change while the reference is alive.
+/// `ub_lab/aliasing_write.rs` mutates the value through a raw pointer while a shared reference to it is still used afterwards.
+/// Natively it prints 2, exactly what the `borrow_checker_fine` demo asserted, which is why the UB is so easy to miss.
+/// The compiler is allowed to assume that `*y` is still 1 and could just as well print that.
+#[test]
+fn aliasing_write() {
+    assert_miri_reports("aliasing_write", Diagnostic::AliasingViolation);
+
+    // the safe version: end the borrow before mutating or use interior mutability
+    let x = std::cell::Cell::new(1);
+    let y = &x;
+
+    x.set(x.get() + 1);
+
+    assert_eq!(y.get(), 2);
+}
+
+/// `ub_lab/reused_allocation.rs` keeps a pointer to the third element of a `Vec` across `pop`, `collect` and `push`.
+/// The value it reads natively (4294967286, which is `-10i32` as `u32`) is an accident of how `collect` reuses allocations.
+/// Miri reports the read as an aliasing violation because the `push` wrote to the element behind the pointer's back.
+#[test]
+fn reused_allocation() {
+    assert_miri_reports("reused_allocation", Diagnostic::AliasingViolation);
+
+    // the safe version: store an index instead of a reference and look it up when needed
+    let mut v: Vec<u32> = vec![1, 2, 3];
+    let index = 2;
+
+    v.pop();
+
+    let mut v: Vec<i32> = v.into_iter().map(|x| x as i32).collect();
+
+    v.push(-10);
+
+    assert_eq!(v.get(index), Some(&-10)); // <- the index is checked against the current `Vec`
+}
+
+/// Growing a `Vec` beyond its capacity moves the elements to a new buffer and frees the old one.
+/// Every pointer into the old buffer dangles.
+#[test]
+fn use_after_free() {
+    assert_miri_reports("use_after_free", Diagnostic::UseAfterFree);
+
+    // the safe version: copy the value out before the `Vec` is modified
+    let mut v: Vec<u32> = Vec::with_capacity(3);
+    v.extend([1, 2, 3]);
+
+    let r1 = v[2];
+
+    v.push(4);
+
+    assert_eq!(r1, 3);
+}
+
+/// `unsafe` does not mean undefined: `ub_lab/defined.rs` uses the same raw pointers as `aliasing_write.rs` but the shared reference is dead before the write.
+/// This also checks that the lab itself works: a program without UB has to pass Miri.
+#[test]
+fn defined_behaviour() {
+    assert_miri_reports("defined", Diagnostic::Defined);
+}
+
+/// The classification of Miri's output is tested without Miri with shortened real error messages.
+#[test]
+fn miri_diagnostics() {
+    let stacked_borrows = "error: Undefined Behavior: attempting a read access using <2880> at alloc1394[0x0], but that tag does not exist in the borrow stack for this location\n\
+        help: this indicates a potential bug in the program: it performed an invalid operation, but the Stacked Borrows rules it violated are still experimental";
+    let use_after_free = "error: Undefined Behavior: memory access failed: alloc878 has been freed, so this pointer is dangling";
+    let out_of_bounds = "error: Undefined Behavior: dereferencing pointer failed: alloc1 has size 12, so pointer to 4 bytes starting at offset 12 is out-of-bounds";
+    let uninitialized = "error: Undefined Behavior: using uninitialized data, but this operation requires initialized memory";
+
+    assert_eq!(
+        Diagnostic::from_miri_output(false, stacked_borrows),
+        Diagnostic::AliasingViolation
+    );
+    assert_eq!(
+        Diagnostic::from_miri_output(false, use_after_free),
+        Diagnostic::UseAfterFree
+    );
+    assert_eq!(
+        Diagnostic::from_miri_output(false, out_of_bounds),
+        Diagnostic::OutOfBounds
+    );
+    assert_eq!(
+        Diagnostic::from_miri_output(false, uninitialized),
+        Diagnostic::Uninitialized
+    );
+    assert_eq!(Diagnostic::from_miri_output(true, ""), Diagnostic::Defined);
+    assert!(matches!(
+        Diagnostic::from_miri_output(false, "error: could not compile"),
+        Diagnostic::Other(_)
+    ));
+}
@@ -0,0 +1,419 @@
+//! https://doc.rust-lang.org/book/ch19-01-unsafe-rust.html
+//! https://doc.rust-lang.org/nomicon/
+//!
+//! Safe Rust guarantees memory safety at compile time. Some correct programs can't be proven correct by the compiler though,
+//! e.g. data structures that manage their own memory or calls into C (see `ffi.rs`).
+//! An `unsafe` block unlocks five additional operations:
+//!
+//! - dereferencing raw pointers
+//! - calling `unsafe` functions (including foreign functions)
+//! - accessing or modifying mutable statics
+//! - implementing `unsafe` traits (`Send`, `Sync`, ..)
+//! - reading fields of `union`s
+//!
+//! `unsafe` does not turn off the borrow checker or any other check. It moves the responsibility for the rules the compiler can't check to the programmer.
+//! Breaking them is undefined behaviour (see `ub_lab.rs`).
+//! The convention is to document every `unsafe` block with a `// SAFETY:` comment that explains why the rules hold,
+//! and to wrap unsafe code in a small safe abstraction whose public API can't be misused.
+//!
+//! The tests of this module are checked for undefined behaviour with Miri by `cargo xtask miri` (part of `cargo xtask`, skipped if Miri isn't installed).
+//!
+//! Dereferencing a raw pointer outside of an `unsafe` block does not compile:
+//!
+//! ```compile_fail,E0133
+//! let x = 1;
+//! let ptr = &x as *const i32;
+//!
+//! assert_eq!(*ptr, 1); // <- dereference of raw pointer is unsafe and requires unsafe block
+//! ```
+//!
+//! Neither does reading a field of a union, because the compiler doesn't know which field was written last:
+//!
+//! ```compile_fail,E0133
+//! union IntOrFloat {
+//!     i: u32,
+//!     f: f32,
+//! }
+//!
+//! let value = IntOrFloat { f: 1.0 };
+//!
+//! assert_eq!(value.i, 0x3f80_0000); // <- access to union field is unsafe and requires unsafe block
+//! ```
+
+/// Raw pointers `*const T` and `*mut T` are like references without the guarantees: they can be null, dangling, unaligned or aliased.
+/// Creating a raw pointer is safe, dereferencing it is not.
+#[test]
+fn raw_pointers() {
+    let mut x = 1;
+
+    let ptr_shared = &x as *const i32; // <- coercion from a reference, the pointer is valid as long as `x` is
+
+    // SAFETY: `ptr_shared` points to `x`, which is alive and initialized
+    assert_eq!(unsafe { *ptr_shared }, 1);
+
+    let ptr_mut = std::ptr::addr_of_mut!(x); // <- creates a raw pointer without creating an intermediate reference
+    let ptr_const = ptr_mut as *const i32; // <- derived from `ptr_mut`, so it stays valid when `ptr_mut` writes
+
+    // a pointer derived from `&x` only allows reads and is invalidated by the write below,
+    // reading `*ptr_shared` afterwards is UB (Miri: "that tag does not exist in the borrow stack")
+
+    // SAFETY: both pointers point to `x`, which is alive and initialized. `ptr_const` is derived from `ptr_mut`.
+    unsafe {
+        *ptr_mut += 1;
+
+        assert_eq!(*ptr_const, 2);
+    }
+
+    // pointers can point to anything, also to memory that is not valid to read
+    let null: *const i32 = std::ptr::null();
+
+    assert!(null.is_null());
+    // SAFETY: `as_ref` checks for null and returns `None`, the pointer is not dereferenced
+    assert_eq!(unsafe { null.as_ref() }, None);
+
+    // pointer arithmetic works in units of `T`, not in bytes
+    let array = [10u16, 20, 30];
+    let first = array.as_ptr();
+
+    // SAFETY: `first + 2` is still inside of `array`
+    let third = unsafe { first.add(2) };
+
+    assert_eq!(
+        third as usize - first as usize,
+        2 * std::mem::size_of::<u16>()
+    );
+    // SAFETY: `third` points to the initialized last element of `array`
+    assert_eq!(unsafe { *third }, 30);
+
+    // `ptr::read` copies the value out without requiring `T: Copy`, `ptr::write` overwrites without dropping the old value
+    let mut name = String::from("iron");
+    let name_ptr = &mut name as *mut String;
+
+    // SAFETY: `name_ptr` is valid. The old `String` is moved out by `read` before `write` overwrites it, so it is dropped exactly once (as `old`).
+    let old = unsafe {
+        let old = std::ptr::read(name_ptr);
+        std::ptr::write(name_ptr, String::from("gold"));
+        old
+    };
+
+    assert_eq!(old, "iron");
+    assert_eq!(name, "gold");
+}
+
+/// A slice `&[T]` is a pointer and a length. `slice::from_raw_parts` builds one from its parts.
+/// This is how slices over foreign memory (e.g. a buffer from C or numpy) are created.
+/// The caller has to guarantee that the pointer is valid for `len` elements and that nothing mutates them while the slice is alive.
+#[test]
+fn slice_from_raw_parts() {
+    let v = [1, 2, 3, 4, 5];
+
+    let ptr = v.as_ptr();
+    let len = v.len();
+
+    // SAFETY: `ptr` points to `len` initialized elements owned by `v`, which outlives `slice` and isn't mutated
+    let slice: &[i32] = unsafe { std::slice::from_raw_parts(ptr, len) };
+
+    assert_eq!(slice, &[1, 2, 3, 4, 5]);
+
+    // SAFETY: elements 1..4 are inside of `v`
+    let middle: &[i32] = unsafe { std::slice::from_raw_parts(ptr.add(1), 3) };
+
+    assert_eq!(middle, &[2, 3, 4]);
+
+    // the safe equivalent is just indexing, which does the bounds check for you
+    assert_eq!(middle, &v[1..4]);
+
+    // a zero length slice still needs a non-null, aligned pointer
+    // SAFETY: `NonNull::dangling` is non-null and aligned, zero elements are read
+    let empty: &[i32] =
+        unsafe { std::slice::from_raw_parts(std::ptr::NonNull::dangling().as_ptr(), 0) };
+
+    assert!(empty.is_empty());
+}
+
+/// `MaybeUninit<T>` is the safe counterpart to `c/src/uninitialized.c`:
+/// it is memory for a `T` that may not be initialized yet, and the type system knows about it.
+/// Reading an uninitialized `int` in C is undefined behaviour that compiles without complaints.
+/// In Rust the value can only be read with `assume_init`, which is `unsafe` and documents the promise "this was initialized".
+/// This is useful for buffers that are filled by C or element by element (`mem::uninitialized` is deprecated because it was always UB for most types).
+#[test]
+fn maybe_uninit() {
+    use std::mem::MaybeUninit;
+
+    let mut num = MaybeUninit::<i32>::uninit(); // <- like `int num;` in C
+
+    // let value = unsafe { num.assume_init() }; // <- UB: Miri reports "using uninitialized data"
+
+    num.write(42); // <- initializing is safe
+
+    // SAFETY: `num` was initialized by `write` above
+    let value = unsafe { num.assume_init() };
+
+    assert_eq!(value, 42);
+
+    // initializing an array element by element
+    let mut squares: [MaybeUninit<u64>; 4] = [MaybeUninit::uninit(); 4];
+
+    for (i, square) in squares.iter_mut().enumerate() {
+        square.write((i * i) as u64);
+    }
+
+    // SAFETY: all 4 elements were written in the loop, `[MaybeUninit<u64>; 4]` and `[u64; 4]` have the same layout
+    let squares: [u64; 4] = unsafe { std::mem::transmute(squares) };
+
+    assert_eq!(squares, [0, 1, 4, 9]);
+
+    // in safe code there is no reason for `MaybeUninit`, `std::array::from_fn` does the same
+    let squares: [u64; 4] = std::array::from_fn(|i| (i * i) as u64);
+
+    assert_eq!(squares, [0, 1, 4, 9]);
+}
+
+/// `Send` (can be moved to another thread) and `Sync` (can be shared between threads) are implemented automatically
+/// if all fields are `Send`/`Sync`. Raw pointers are neither, so a type containing a raw pointer is neither.
+/// If the type upholds the guarantees anyway it can opt in with `unsafe impl`. The compiler trusts the `unsafe impl` blindly.
+#[test]
+fn unsafe_impl_send() {
+    // owns a heap allocated value through a raw pointer, like a minimal `Box`
+    struct RawBox<T> {
+        ptr: *mut T,
+    }
+
+    impl<T> RawBox<T> {
+        fn new(value: T) -> Self {
+            Self {
+                ptr: Box::into_raw(Box::new(value)),
+            }
+        }
+
+        fn get(&self) -> &T {
+            // SAFETY: `ptr` comes from `Box::into_raw` and is only freed in `drop`
+            unsafe { &*self.ptr }
+        }
+    }
+
+    impl<T> Drop for RawBox<T> {
+        fn drop(&mut self) {
+            // SAFETY: `ptr` comes from `Box::into_raw` and `drop` runs only once
+            drop(unsafe { Box::from_raw(self.ptr) });
+        }
+    }
+
+    assert_not_impl_any!(*mut i32: Send, Sync); // <- the raw pointer field would make `RawBox` `!Send` and `!Sync`
+
+    // SAFETY: `RawBox` owns its value exclusively (nobody else has the pointer), so moving it to another thread moves the value, like `Box<T>`
+    unsafe impl<T: Send> Send for RawBox<T> {}
+
+    assert_impl_all!(RawBox<String>: Send);
+    assert_not_impl_any!(RawBox<std::rc::Rc<i32>>: Send); // <- the bound still matters: an `Rc` must not be sent to another thread
+
+    let boxed = RawBox::new(String::from("moved to another thread"));
+
+    let length = std::thread::spawn(move || boxed.get().len())
+        .join()
+        .unwrap();
+
+    assert_eq!(length, 23);
+}
+
+/// A `union` stores all fields at the same location, like a C `union`. Only one of them is valid at a time and the compiler doesn't track which one.
+/// Reading a field reinterprets the bytes as the field's type, therefore it is `unsafe`.
+/// Unions are mostly used for FFI. In Rust an `enum` is the safe alternative: it stores a tag that says which variant is valid.
+#[test]
+fn unions() {
+    #[repr(C)]
+    union IntOrFloat {
+        i: u32,
+        f: f32,
+    }
+
+    let mut value = IntOrFloat { f: 1.0 };
+
+    // SAFETY: every bit pattern is a valid `u32`
+    assert_eq!(unsafe { value.i }, 0x3f80_0000); // <- the IEEE 754 representation of 1.0
+    assert_eq!(1.0f32.to_bits(), 0x3f80_0000); // <- the safe way to get the same bits
+
+    value.i = 0x4000_0000; // <- writing a field is safe, it can't create an invalid value for the written field
+
+    // SAFETY: every bit pattern is a valid `f32`
+    assert_eq!(unsafe { value.f }, 2.0);
+
+    assert_eq!(std::mem::size_of::<IntOrFloat>(), 4); // <- as large as the largest field
+
+    // fields that need to be dropped have to be wrapped in `ManuallyDrop`, the union doesn't know which one to drop
+    union StringOrNumber {
+        string: std::mem::ManuallyDrop<String>,
+        number: u64,
+    }
+
+    let mut value = StringOrNumber {
+        string: std::mem::ManuallyDrop::new(String::from("hello")),
+    };
+
+    // SAFETY: `string` is the field that was written last
+    unsafe {
+        assert_eq!(*value.string, "hello");
+
+        std::mem::ManuallyDrop::drop(&mut value.string); // <- dropped by hand, otherwise the `String` leaks
+    }
+
+    value.number = 42;
+
+    // SAFETY: `number` is the field that was written last
+    assert_eq!(unsafe { value.number }, 42);
+}
+
+/// Splits a slice into two mutable halves at `mid`: `[0, mid)` and `[mid, len)`.
+///
+/// `split_at_mut` can't be written in safe Rust: the borrow checker doesn't know that the two halves don't overlap.
+/// The unsafe implementation is hidden behind a safe function. The `assert!` makes sure that no input can cause UB,
+/// so callers can't misuse it -> a sound safe abstraction.
+///
+/// ```
+/// use rust_snippets::unsafe_rust::split_at_mut;
+///
+/// let mut numbers = [1, 2, 3, 4, 5];
+/// let (left, right) = split_at_mut(&mut numbers, 2);
+///
+/// left[0] = 10;
+/// right[0] = 30; // <- both halves are mutable at the same time
+///
+/// assert_eq!(numbers, [10, 2, 30, 4, 5]);
+/// ```
+///
+/// A `mid` outside of the slice panics instead of creating slices that point outside of it:
+///
+/// ```should_panic
+/// rust_snippets::unsafe_rust::split_at_mut(&mut [1, 2, 3], 4); // panics: assertion failed: mid <= len
+/// ```
+pub fn split_at_mut<T>(slice: &mut [T], mid: usize) -> (&mut [T], &mut [T]) {
+    // let (left, right) = (&mut slice[..mid], &mut slice[mid..]); // <- Error: cannot borrow `*slice` as mutable more than once at a time
+
+    let len = slice.len();
+    let ptr = slice.as_mut_ptr();
+
+    assert!(mid <= len); // <- without this check the function would be unsound: safe code could create out-of-bounds slices
+
+    // SAFETY: `mid <= len`, so `[0, mid)` and `[mid, len)` are inside of `slice` and don't overlap.
+    // Both slices borrow from `slice`, so they can't outlive it and nobody else can access it while they exist.
+    unsafe {
+        (
+            std::slice::from_raw_parts_mut(ptr, mid),
+            std::slice::from_raw_parts_mut(ptr.add(mid), len - mid),
+        )
+    }
+}
+
+/// The safe abstraction `split_at_mut` (above) in use. Miri checks that the unsafe code inside of it is sound for these inputs.
+#[test]
+fn safe_abstraction_split_at_mut() {
+    let mut numbers = [1, 2, 3, 4, 5];
+
+    let (left, right) = split_at_mut(&mut numbers, 2);
+
+    left[0] = 10;
+    right[0] = 30; // <- both halves are mutable at the same time
+
+    assert_eq!(left, &[10, 2]);
+    assert_eq!(right, &[30, 4, 5]);
+    assert_eq!(numbers, [10, 2, 30, 4, 5]);
+
+    let (left, right) = split_at_mut(&mut numbers, 5);
+
+    assert_eq!(left.len(), 5);
+    assert!(right.is_empty());
+
+    assert!(std::panic::catch_unwind(|| {
+        split_at_mut(&mut [1, 2, 3], 4);
+    })
+    .is_err()); // <- panics instead of UB
+}
+
+/// A stack with a fixed capacity that stores its elements inline, without heap allocation.
+/// The invariant is: the first `len` elements of `buffer` are initialized, the rest are not.
+/// Every `unsafe` block relies on it and every method that changes `len` maintains it.
+#[test]
+fn safe_abstraction_stack() {
+    use std::mem::MaybeUninit;
+
+    struct FixedStack<T, const N: usize> {
+        buffer: [MaybeUninit<T>; N],
+        len: usize, // <- invariant: `buffer[..len]` is initialized
+    }
+
+    impl<T, const N: usize> FixedStack<T, N> {
+        fn new() -> Self {
+            Self {
+                buffer: std::array::from_fn(|_| MaybeUninit::uninit()),
+                len: 0,
+            }
+        }
+
+        /// Returns the value back if the stack is full.
+        fn push(&mut self, value: T) -> Result<(), T> {
+            if self.len == N {
+                return Err(value);
+            }
+
+            self.buffer[self.len].write(value);
+            self.len += 1; // <- only after the write, `buffer[..len]` stays initialized
+
+            Ok(())
+        }
+
+        fn pop(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+
+            self.len -= 1; // <- before the read, the moved out element must not be read or dropped again
+
+            // SAFETY: `buffer[len]` was initialized (it was inside of `buffer[..len + 1]`) and is now outside of the initialized range
+            Some(unsafe { self.buffer[self.len].assume_init_read() })
+        }
+
+        fn as_slice(&self) -> &[T] {
+            // SAFETY: `buffer[..len]` is initialized and `MaybeUninit<T>` has the same layout as `T`
+            unsafe { std::slice::from_raw_parts(self.buffer.as_ptr().cast::<T>(), self.len) }
+        }
+    }
+
+    impl<T, const N: usize> Drop for FixedStack<T, N> {
+        fn drop(&mut self) {
+            for element in &mut self.buffer[..self.len] {
+                // SAFETY: `buffer[..len]` is initialized and every element is dropped exactly once
+                unsafe { element.assume_init_drop() };
+            }
+        }
+    }
+
+    let mut stack: FixedStack<String, 3> = FixedStack::new();
+
+    assert_eq!(stack.pop(), None);
+
+    stack.push("iron".to_string()).unwrap();
+    stack.push("gold".to_string()).unwrap();
+    stack.push("copper".to_string()).unwrap();
+
+    assert_eq!(stack.push("silver".to_string()), Err("silver".to_string())); // <- full
+    assert_eq!(stack.as_slice(), ["iron", "gold", "copper"]);
+    assert_eq!(stack.pop().as_deref(), Some("copper"));
+    assert_eq!(stack.as_slice(), ["iron", "gold"]);
+
+    // the remaining elements are dropped exactly once when the stack is dropped (Miri reports leaks and double frees)
+    let counter = std::rc::Rc::new(());
+
+    {
+        let mut stack: FixedStack<std::rc::Rc<()>, 4> = FixedStack::new();
+
+        stack.push(counter.clone()).unwrap();
+        stack.push(counter.clone()).unwrap();
+        stack.push(counter.clone()).unwrap();
+
+        drop(stack.pop());
+
+        assert_eq!(std::rc::Rc::strong_count(&counter), 3);
+    }
+
+    assert_eq!(std::rc::Rc::strong_count(&counter), 1);
+}
@@ -1,4 +1,28 @@
 //! Variables in Rust are defined with the `let` keyword and require an explicit type annotation if it can't be deterministically inferred.
+//!
+//! Variables are immutable unless they are declared with `mut`:
+//!
+//! ```compile_fail,E0384
+//! let weight = 55.845;
+//!
+//! weight = 55.8; // <- cannot assign twice to immutable variable
+//! ```
+
+/// Parses an atomic weight like `" 55.845 "`. Shadowing reuses the name `text` for the trimmed text,
+/// the type annotation of `weight` tells `parse` which type to parse into.
+///
+/// ```
+/// use rust_snippets::variables::parse_weight;
+///
+/// assert_eq!(parse_weight(" 55.845 "), Some(55.845));
+/// assert_eq!(parse_weight("iron"), None);
+/// ```
+pub fn parse_weight(text: &str) -> Option<f32> {
+    let text = text.trim(); // <- shadows the parameter, the untrimmed text can't be used by mistake anymore
+    let weight: f32 = text.parse().ok()?; // <- without the annotation `parse` wouldn't know the type
+
+    Some(weight)
+}
 
 /// Mutability is a property of variables that determines whether they can be changed after they have been defined.
 /// Variables are immutable by default. To make them mutable the `mut` keyword has to be used.
@@ -165,7 +189,7 @@ fn constant_expression() {
         }
     }); // match expressions are constant expressions
 
-    const CL: &'static dyn Fn(u32) -> u32 = &|x: u32| x + 1; // closures are constant expressions
+    const CL: &'static dyn Fn(u32) -> u32 = &|x: u32| x + 1; // closures are constant expressions (explained in `functions.rs`, `constant_closures`)
 
     // const_assert_eq!(CL(1), 2); // function call expressions are not constant expressions ?
     assert_eq!(CL(1), 2);
@@ -0,0 +1,13 @@
+// `borrow_checker_fine` without the borrow checker: a shared reference is alive while the value is mutated.
+// The borrow checker rejects this with `&`/`&mut`, raw pointers let it compile.
+
+fn main() {
+    let mut x = Box::new(1);
+    let x_ptr: *mut i32 = &mut *x;
+
+    let y: &i32 = unsafe { &*x_ptr }; // <- shared reference, promises that the value does not change while `y` is alive
+
+    unsafe { *x_ptr += 1 }; // <- write through the raw pointer invalidates `y`
+
+    println!("{}", *y); // UB: `y` is used after it was invalidated (prints 2 natively, which looks "fine")
+}
@@ -0,0 +1,15 @@
+// The same raw pointers as in `aliasing_write.rs`, but every reference is dead before the value is mutated.
+// Miri runs this without complaints: unsafe code is not automatically undefined behaviour.
+
+fn main() {
+    let mut x = Box::new(1);
+    let x_ptr: *mut i32 = &mut *x;
+
+    let y: &i32 = unsafe { &*x_ptr };
+    println!("{}", *y); // <- last use of `y`
+
+    unsafe { *x_ptr += 1 };
+
+    println!("{}", unsafe { *x_ptr });
+    println!("{}", x);
+}
@@ -0,0 +1,16 @@
+// `borrow_checker_undefined` without the borrow checker: a pointer into a `Vec` survives `pop`, `collect` and `push`.
+// The mysterious `4294967286` is `-10i32` read as `u32`: `collect` reuses the allocation in place
+// because `u32` and `i32` have the same layout, so the old pointer now points at the new element.
+
+fn main() {
+    let mut v: Vec<u32> = vec![1, 2, 3];
+    let r1: *const u32 = &v[2]; // <- derived from a shared reference to the third element
+
+    v.pop();
+
+    let mut v: Vec<i32> = v.into_iter().map(|x| x as i32).collect(); // <- same allocation, new type
+
+    v.push(-10); // <- writes the third element through the `Vec`, invalidates `r1`
+
+    println!("{}", unsafe { *r1 }); // UB: prints 4294967286 natively, but only because of an implementation detail of `collect`
+}
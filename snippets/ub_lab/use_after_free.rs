@@ -0,0 +1,12 @@
+// A pointer into a `Vec` survives a reallocation. The old buffer is freed and the pointer dangles.
+
+fn main() {
+    let mut v: Vec<u32> = Vec::with_capacity(3);
+    v.extend([1, 2, 3]);
+
+    let r1: *const u32 = &v[2];
+
+    v.push(4); // <- capacity exceeded: the elements are moved to a new buffer and the old one is freed
+
+    println!("{}", unsafe { *r1 }); // UB: reads freed memory (natively prints 3 or garbage, depending on the allocator)
+}
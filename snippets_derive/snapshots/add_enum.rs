@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "`#[derive(Add)]` is not supported for enums, adding two different variants has no meaning"
+}
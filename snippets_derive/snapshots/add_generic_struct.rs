@@ -0,0 +1,13 @@
+impl<T> ::core::ops::Add for Point<T>
+where
+    T: Copy,
+    T: ::core::ops::Add<Output = T>,
+{
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            x: ::core::ops::Add::add(self.x, other.x),
+            y: ::core::ops::Add::add(self.y, other.y),
+        }
+    }
+}
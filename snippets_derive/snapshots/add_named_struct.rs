@@ -0,0 +1,9 @@
+impl ::core::ops::Add for Animal {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            name: concat(self.name, other.name),
+            age: ::core::ops::Add::add(self.age, other.age),
+        }
+    }
+}
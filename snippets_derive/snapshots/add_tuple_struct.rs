@@ -0,0 +1,9 @@
+impl ::core::ops::Add for Point {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self(
+            ::core::ops::Add::add(self.0, other.0),
+            ::core::ops::Add::add(self.1, other.1),
+        )
+    }
+}
@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "`#[derive(Add)]` requires a struct with at least one field"
+}
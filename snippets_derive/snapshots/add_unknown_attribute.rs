@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "unknown `add` attribute, expected `with = \"function\"`"
+}
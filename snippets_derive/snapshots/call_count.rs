@@ -0,0 +1,10 @@
+static __ADD_CALL_COUNT: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(
+    0,
+);
+pub fn add(a: i32, b: i32) -> i32 {
+    __ADD_CALL_COUNT.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+    a + b
+}
+pub fn add_call_count() -> usize {
+    __ADD_CALL_COUNT.load(::std::sync::atomic::Ordering::Relaxed)
+}
@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "`#[call_count]` takes no arguments"
+}
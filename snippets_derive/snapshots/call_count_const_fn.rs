@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "`#[call_count]` is not supported for `const fn`, a counter can't be incremented at compile time"
+}
@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "`#[call_count]` only supports free functions, a method can't have a `static` counter next to it in the `impl` block"
+}
@@ -0,0 +1,11 @@
+impl Molecule for Methane {
+    fn name(&self) -> String {
+        "Methane".to_string()
+    }
+    fn weight(&self) -> f32 {
+        16.043
+    }
+    fn is_organic(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,11 @@
+impl Molecule for Water {
+    fn name(&self) -> String {
+        "Water".to_string()
+    }
+    fn weight(&self) -> f32 {
+        18.015
+    }
+    fn is_organic(&self) -> bool {
+        false
+    }
+}
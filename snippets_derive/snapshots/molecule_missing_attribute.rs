@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "`#[derive(Molecule)]` requires a `#[molecule(weight = ...)]` attribute"
+}
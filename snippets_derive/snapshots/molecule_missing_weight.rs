@@ -0,0 +1,3 @@
+::core::compile_error! {
+    "missing `weight = ...`"
+}
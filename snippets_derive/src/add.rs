@@ -0,0 +1,182 @@
+//! `#[derive(Add)]` -> `impl Add for Type` adding the fields of two values one by one.
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Data, DeriveInput, Fields, Index, Member};
+
+pub(crate) fn expand(input: TokenStream) -> TokenStream {
+    syn::parse2(input)
+        .and_then(derive)
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn derive(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span(),
+                "`#[derive(Add)]` is not supported for enums, adding two different variants has no meaning",
+            ))
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`#[derive(Add)]` is not supported for unions",
+            ))
+        }
+    };
+
+    if fields.is_empty() {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "`#[derive(Add)]` requires a struct with at least one field",
+        ));
+    }
+
+    let mut sums = Vec::new();
+    let mut bounds: Vec<(String, syn::WherePredicate)> = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+
+        let sum = match with_function(field)? {
+            Some(function) => quote_spanned!(field.span()=> #function(self.#member, other.#member)),
+            None => {
+                let ty = &field.ty;
+                // one bound per field type
+                if !bounds
+                    .iter()
+                    .any(|(bounded, _)| *bounded == quote!(#ty).to_string())
+                {
+                    bounds.push((
+                        quote!(#ty).to_string(),
+                        parse_quote!(#ty: ::core::ops::Add<Output = #ty>),
+                    ));
+                }
+                quote_spanned!(field.span()=> ::core::ops::Add::add(self.#member, other.#member))
+            }
+        };
+
+        sums.push(match fields {
+            Fields::Named(_) => quote!(#member: #sum),
+            _ => sum,
+        });
+    }
+
+    let body = match fields {
+        Fields::Named(_) => quote!(Self { #(#sums,)* }),
+        _ => quote!(Self(#(#sums,)*)),
+    };
+
+    // every field type has to be addable -> generic structs only implement `Add` if their parameters allow it
+    if input.generics.type_params().next().is_some() {
+        input
+            .generics
+            .make_where_clause()
+            .predicates
+            .extend(bounds.into_iter().map(|(_, bound)| bound));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::ops::Add for #name #type_generics #where_clause {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Reads the function of `#[add(with = "function")]`.
+fn with_function(field: &syn::Field) -> syn::Result<Option<syn::ExprPath>> {
+    let mut function = None;
+
+    for attribute in field
+        .attrs
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("add"))
+    {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                function = Some(value.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown `add` attribute, expected `with = \"function\"`"))
+            }
+        })?;
+    }
+
+    Ok(function)
+}
+
+#[test]
+fn tuple_struct() {
+    crate::assert_snapshot(
+        "add_tuple_struct",
+        expand(quote! {
+            struct Point(f32, f32);
+        }),
+    );
+}
+
+#[test]
+fn named_struct() {
+    crate::assert_snapshot(
+        "add_named_struct",
+        expand(quote! {
+            struct Animal {
+                #[add(with = "concat")]
+                name: String,
+                age: u32,
+            }
+        }),
+    );
+}
+
+#[test]
+fn generic_struct() {
+    crate::assert_snapshot(
+        "add_generic_struct",
+        expand(quote! {
+            struct Point<T> where T: Copy {
+                x: T,
+                y: T,
+            }
+        }),
+    );
+}
+
+#[test]
+fn unsupported_shapes() {
+    crate::assert_snapshot(
+        "add_enum",
+        expand(quote!(
+            enum Color {
+                Red,
+                Green,
+            }
+        )),
+    );
+    crate::assert_snapshot(
+        "add_unit_struct",
+        expand(quote!(
+            struct Unit;
+        )),
+    );
+    crate::assert_snapshot(
+        "add_unknown_attribute",
+        expand(quote!(
+            struct Point(#[add(using = "f")] f32);
+        )),
+    );
+}
@@ -0,0 +1,146 @@
+//! `#[call_count]` -> the function increments a static counter on every call and gets a companion function returning it.
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::ItemFn;
+
+pub(crate) fn expand(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    attribute_macro(attribute, item).unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn attribute_macro(attribute: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    if !attribute.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attribute,
+            "`#[call_count]` takes no arguments",
+        ));
+    }
+
+    let mut function: ItemFn = syn::parse2(item)?;
+
+    if let Some(constness) = &function.sig.constness {
+        return Err(syn::Error::new_spanned(
+            constness,
+            "`#[call_count]` is not supported for `const fn`, a counter can't be incremented at compile time",
+        ));
+    }
+
+    // the counter is a `static` next to the function, which can't be declared inside an `impl` block.
+    // A method can't be told apart from a free function by its tokens alone, but a receiver or a `Self` in the signature gives it away
+    if let Some(receiver) = function.sig.receiver() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "`#[call_count]` only supports free functions, a method can't have a `static` counter next to it in the `impl` block",
+        ));
+    }
+
+    if let Some(self_type) = mentions_self(&function.sig) {
+        return Err(syn::Error::new_spanned(
+            self_type,
+            "`#[call_count]` only supports free functions, an associated function can't have a `static` counter next to it in the `impl` block",
+        ));
+    }
+
+    let name = &function.sig.ident;
+    let vis = &function.vis;
+    let counter = format_ident!("__{}_CALL_COUNT", name.to_string().to_uppercase());
+    let getter = format_ident!("{}_call_count", name);
+
+    // increment the counter before the original body runs
+    let statements = &function.block.stmts;
+    function.block = syn::parse_quote!({
+        #counter.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+        #(#statements)*
+    });
+
+    Ok(quote! {
+        static #counter: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(0);
+
+        #function
+
+        #vis fn #getter() -> usize {
+            #counter.load(::std::sync::atomic::Ordering::Relaxed)
+        }
+    })
+}
+
+/// The first `Self` in the signature, e.g. in `fn new() -> Self`.
+fn mentions_self(signature: &syn::Signature) -> Option<syn::Ident> {
+    fn find(tokens: TokenStream) -> Option<syn::Ident> {
+        tokens.into_iter().find_map(|token| match token {
+            TokenTree::Ident(ident) if ident == "Self" => Some(ident),
+            TokenTree::Group(group) => find(group.stream()), // <- inside `(..)`, `[..]` and `{..}`
+            _ => None,
+        })
+    }
+
+    find(signature.to_token_stream())
+}
+
+#[test]
+fn call_count() {
+    crate::assert_snapshot(
+        "call_count",
+        expand(
+            TokenStream::new(),
+            quote! {
+                pub fn add(a: i32, b: i32) -> i32 {
+                    a + b
+                }
+            },
+        ),
+    );
+}
+
+#[test]
+fn call_count_errors() {
+    crate::assert_snapshot(
+        "call_count_arguments",
+        expand(
+            quote!(verbose),
+            quote!(
+                fn add() {}
+            ),
+        ),
+    );
+    crate::assert_snapshot(
+        "call_count_const_fn",
+        expand(
+            TokenStream::new(),
+            quote!(
+                const fn add() {}
+            ),
+        ),
+    );
+    crate::assert_snapshot(
+        "call_count_method",
+        expand(
+            TokenStream::new(),
+            quote!(
+                fn add(&self, other: i32) -> i32 {
+                    self.0 + other
+                }
+            ),
+        ),
+    );
+    crate::assert_snapshot(
+        "call_count_associated_function",
+        expand(
+            TokenStream::new(),
+            quote!(
+                fn new() -> Self {
+                    Self(0)
+                }
+            ),
+        ),
+    );
+    crate::assert_snapshot(
+        "call_count_struct",
+        expand(
+            TokenStream::new(),
+            quote!(
+                struct Add;
+            ),
+        ),
+    );
+}
@@ -0,0 +1,67 @@
+//! Procedural macros for the macro lessons (see `snippets/src/macros.rs`).
+//!
+//! Procedural macros are functions that run at compile time, take the tokens of the annotated item as input and return new tokens.
+//! They have to live in their own crate with `proc-macro = true`, because the compiler has to build and run them before compiling the crate that uses them.
+//!
+//! There are three kinds of procedural macros:
+//! - derive macros `#[derive(Add)]` add new items (usually trait implementations) next to the annotated struct or enum
+//! - attribute macros `#[call_count]` replace the annotated item
+//! - function-like macros `sql!(...)` are called like `macro_rules!` macros
+//!
+//! Every macro is implemented on `proc_macro2::TokenStream` so that the expansion can be tested with snapshots outside of the compiler.
+//! Unsupported input is reported with `compile_error!` at the location of the problem instead of panicking.
+
+use proc_macro::TokenStream;
+
+mod add;
+mod call_count;
+mod molecule;
+
+/// Derives `std::ops::Add` by adding the structs field by field.
+///
+/// Every field type has to implement `Add<Output = FieldType>`,
+/// unless the field is annotated with `#[add(with = "function")]`, which combines the two fields with `function(self.field, other.field)`.
+#[proc_macro_derive(Add, attributes(add))]
+pub fn derive_add(input: TokenStream) -> TokenStream {
+    add::expand(input.into()).into()
+}
+
+/// Derives the `Molecule` trait of the traits lesson from a `#[molecule(...)]` attribute.
+///
+/// `#[molecule(weight = 18.015)]` is required, `name = "Water"` defaults to the name of the type and `organic = true` defaults to `false`.
+/// The `Molecule` trait has to be in scope.
+#[proc_macro_derive(Molecule, attributes(molecule))]
+pub fn derive_molecule(input: TokenStream) -> TokenStream {
+    molecule::expand(input.into()).into()
+}
+
+/// Counts how often a function is called.
+///
+/// `#[call_count] fn add(..)` generates a function `add_call_count()` returning the number of calls so far.
+/// Only free functions are supported: methods and associated functions are a compile error, the counter can't be declared in an `impl` block.
+#[proc_macro_attribute]
+pub fn call_count(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    call_count::expand(attribute.into(), item.into()).into()
+}
+
+/// Compares the pretty printed expansion with the snapshot in `snapshots/{name}.rs`.
+/// `UPDATE_SNAPSHOTS=1 cargo test` writes the current expansion to the snapshot instead.
+#[cfg(test)]
+fn assert_snapshot(name: &str, expansion: proc_macro2::TokenStream) {
+    let file = syn::parse2::<syn::File>(expansion).expect("the expansion is not valid Rust");
+    let expansion = prettyplease::unparse(&file);
+
+    let path = format!("{}/snapshots/{name}.rs", env!("CARGO_MANIFEST_DIR"));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &expansion).unwrap();
+    }
+
+    let snapshot = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing snapshot {path}, run with UPDATE_SNAPSHOTS=1"));
+
+    assert_eq!(
+        expansion, snapshot,
+        "the expansion changed, run with UPDATE_SNAPSHOTS=1 to accept it"
+    );
+}
@@ -0,0 +1,113 @@
+//! `#[derive(Molecule)]` -> `impl Molecule for Type` with the values of the `#[molecule(...)]` attribute.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitBool, LitFloat, LitStr};
+
+pub(crate) fn expand(input: TokenStream) -> TokenStream {
+    syn::parse2(input)
+        .and_then(derive)
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let mut name = None;
+    let mut weight = None;
+    let mut organic = None;
+
+    let attribute = input
+        .attrs
+        .iter()
+        .find(|attribute| attribute.path().is_ident("molecule"))
+        .ok_or_else(|| {
+            syn::Error::new(
+                input.ident.span(),
+                "`#[derive(Molecule)]` requires a `#[molecule(weight = ...)]` attribute",
+            )
+        })?;
+
+    attribute.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse::<LitStr>()?);
+        } else if meta.path.is_ident("weight") {
+            weight = Some(meta.value()?.parse::<LitFloat>()?);
+        } else if meta.path.is_ident("organic") {
+            organic = Some(meta.value()?.parse::<LitBool>()?);
+        } else {
+            return Err(
+                meta.error("unknown `molecule` attribute, expected `name`, `weight` or `organic`")
+            );
+        }
+        Ok(())
+    })?;
+
+    let weight =
+        weight.ok_or_else(|| syn::Error::new_spanned(attribute, "missing `weight = ...`"))?;
+    let name = name.unwrap_or_else(|| LitStr::new(&input.ident.to_string(), input.ident.span()));
+    let organic = organic.map(|organic| organic.value).unwrap_or(false);
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics Molecule for #ident #type_generics #where_clause {
+            fn name(&self) -> String {
+                #name.to_string()
+            }
+
+            fn weight(&self) -> f32 {
+                #weight
+            }
+
+            fn is_organic(&self) -> bool {
+                #organic
+            }
+        }
+    })
+}
+
+#[test]
+fn molecule() {
+    crate::assert_snapshot(
+        "molecule",
+        expand(quote! {
+            #[molecule(name = "Methane", weight = 16.043, organic = true)]
+            struct Methane;
+        }),
+    );
+}
+
+#[test]
+fn molecule_defaults() {
+    crate::assert_snapshot(
+        "molecule_defaults",
+        expand(quote! {
+            #[molecule(weight = 18.015)]
+            struct Water;
+        }),
+    );
+}
+
+#[test]
+fn molecule_errors() {
+    crate::assert_snapshot(
+        "molecule_missing_attribute",
+        expand(quote!(
+            struct Water;
+        )),
+    );
+    crate::assert_snapshot(
+        "molecule_missing_weight",
+        expand(quote! {
+            #[molecule(name = "Water")]
+            struct Water;
+        }),
+    );
+    crate::assert_snapshot(
+        "molecule_wrong_type",
+        expand(quote! {
+            #[molecule(weight = "heavy")]
+            struct Water;
+        }),
+    );
+}
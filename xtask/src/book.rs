@@ -0,0 +1,1069 @@
+//! Renders the lessons in `snippets/src/*.rs` as a static HTML site (`cargo xtask book`, written to `target/book`).
+//!
+//! Every file is a chapter: the `//!` module documentation is its introduction, every top level item
+//! (mostly `#[test]` functions) is a section with its `///` documentation and its code.
+//! Comparisons with other languages are shown next to the Rust code:
+//! C and C++ in block comments that start with a file name (`/* uninitialized.c ... */`, see safety.rs and cpp.rs)
+//! and Python in the strings passed to `py.run` or `run_python_with` (see errror_handling.rs and numpy.rs).
+//!
+//! The site has no external dependencies (no mdBook, no JavaScript, no web fonts), the syntax highlighting is done here.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Language {
+    Rust,
+    C,
+    Python,
+    Plain,
+}
+
+#[derive(Debug, PartialEq)]
+struct Comparison {
+    language: Language,
+    title: String,
+    code: String,
+}
+
+#[derive(Debug)]
+struct Section {
+    name: String,
+    doc: Vec<String>,
+    attributes: Vec<String>,
+    code: String,
+    comparisons: Vec<Comparison>,
+}
+
+#[derive(Debug)]
+struct Chapter {
+    name: String,
+    doc: Vec<String>,
+    sections: Vec<Section>,
+}
+
+/// Writes the site to `target/book` (or `$CARGO_TARGET_DIR/book`) and returns the path of `index.html`.
+pub fn build(root: &Path, target: &Path) -> Result<PathBuf, String> {
+    let source_dir = root.join("snippets").join("src");
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map_err(|error| format!("{} could not be read: {error}", path.display()))
+    };
+
+    let lib = read(&source_dir.join("lib.rs"))?;
+
+    let mut names: Vec<String> = std::fs::read_dir(&source_dir)
+        .map_err(|error| format!("{} could not be read: {error}", source_dir.display()))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|file| file.strip_suffix(".rs").map(str::to_string))
+        .filter(|name| name != "lib" && name != "harness") // <- the harness is used by the tests, it's not a lesson
+        .collect();
+
+    // the order of the course in lib.rs, chapters that are not listed there come last
+    let order = chapter_order(&lib);
+    names.sort_by_key(|name| {
+        (
+            order
+                .iter()
+                .position(|listed| listed == name)
+                .unwrap_or(usize::MAX),
+            name.clone(),
+        )
+    });
+
+    let mut chapters = Vec::new();
+
+    for name in names {
+        let source = read(&source_dir.join(format!("{name}.rs")))?;
+        chapters.push(parse_chapter(&name, &source));
+    }
+
+    let out = target.join("book");
+    std::fs::create_dir_all(&out)
+        .map_err(|error| format!("{} could not be created: {error}", out.display()))?;
+
+    let write = |file: &str, content: String| {
+        std::fs::write(out.join(file), content)
+            .map_err(|error| format!("{file} could not be written: {error}"))
+    };
+
+    let (lib_doc, _) = module_doc(&lib);
+
+    write("style.css", STYLE.to_string())?;
+    write("index.html", render_index(&lib_doc, &chapters))?;
+
+    for chapter in &chapters {
+        write(
+            &format!("{}.html", chapter.name),
+            render_chapter(chapter, &chapters),
+        )?;
+    }
+
+    Ok(out.join("index.html"))
+}
+
+/// The chapter names in the order of the links in the course list (``[`name`]``) of lib.rs.
+fn chapter_order(lib: &str) -> Vec<String> {
+    let mut order = Vec::new();
+
+    for line in lib.lines().filter(|line| line.starts_with("//!")) {
+        let mut rest = line;
+
+        while let Some(start) = rest.find("[`") {
+            let Some(end) = rest[start + 2..].find("`]") else {
+                break;
+            };
+
+            order.push(rest[start + 2..start + 2 + end].to_string());
+            rest = &rest[start + 2 + end..];
+        }
+    }
+
+    order
+}
+
+/// The `//!` lines at the top of the file and the rest of the file.
+fn module_doc(source: &str) -> (Vec<String>, &str) {
+    let mut doc = Vec::new();
+    let mut offset = 0;
+
+    for line in source.split_inclusive('\n') {
+        let Some(text) = line.trim_end().strip_prefix("//!") else {
+            break;
+        };
+
+        doc.push(text.strip_prefix(' ').unwrap_or(text).to_string());
+        offset += line.len();
+    }
+
+    (doc, &source[offset..])
+}
+
+fn parse_chapter(name: &str, source: &str) -> Chapter {
+    let (doc, rest) = module_doc(source);
+
+    let mut sections = Vec::new();
+    let mut pending_doc = Vec::new();
+    let mut attributes = Vec::new();
+    let mut offset = 0;
+
+    while offset < rest.len() {
+        let line_end = rest[offset..]
+            .find('\n')
+            .map_or(rest.len(), |end| offset + end + 1);
+        let line = rest[offset..line_end].trim();
+
+        if let Some(text) = line.strip_prefix("///") {
+            pending_doc.push(text.strip_prefix(' ').unwrap_or(text).to_string());
+            offset = line_end;
+        } else if line.starts_with("#[") || line.starts_with("#![") {
+            let end = item_end(rest, offset);
+            attributes.push(rest[offset..end].trim().to_string());
+            offset = end;
+        } else if line.is_empty() || line.starts_with("//") {
+            offset = line_end;
+        } else {
+            let end = item_end(rest, offset);
+            let code = dedent(&rest[offset..end]);
+
+            let helper = attributes
+                .iter()
+                .any(|attribute| attribute == "#[cfg(test)]");
+            let import = line.starts_with("use ") || line.starts_with("pub use ");
+
+            if !helper && !import {
+                let (code, comparisons) = extract_comparisons(&code);
+
+                sections.push(Section {
+                    name: item_name(line),
+                    doc: std::mem::take(&mut pending_doc),
+                    attributes: std::mem::take(&mut attributes),
+                    code,
+                    comparisons,
+                });
+            }
+
+            pending_doc.clear();
+            attributes.clear();
+            offset = end;
+        }
+    }
+
+    Chapter {
+        name: name.to_string(),
+        doc,
+        sections,
+    }
+}
+
+/// A readable name for the item that starts with `line`, e.g. `closure` for `fn closure() {`.
+fn item_name(line: &str) -> String {
+    let line = line.trim_end_matches('{').trim();
+
+    for keyword in [
+        "fn ",
+        "struct ",
+        "enum ",
+        "trait ",
+        "union ",
+        "macro_rules! ",
+        "mod ",
+        "const ",
+        "static ",
+    ] {
+        if let Some(index) = line.find(keyword) {
+            let rest = &line[index + keyword.len()..];
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+
+            return match keyword {
+                "macro_rules! " => format!("{}!", &rest[..end]),
+                _ => rest[..end].to_string(),
+            };
+        }
+    }
+
+    line.to_string()
+}
+
+/// The end (exclusive, at a line end) of the item or attribute that starts at `start`:
+/// after the `]` of an attribute, the `}` of an item's body or a `;` outside of brackets.
+/// Strings, chars and comments are skipped, so brackets inside of them don't count.
+fn item_end(source: &str, start: usize) -> usize {
+    let chars: Vec<(usize, char)> = source[start..].char_indices().collect();
+    let closing = if source[start..].starts_with('#') {
+        ']'
+    } else {
+        '}'
+    };
+    let at = |i: usize| chars.get(i).map(|(_, c)| *c);
+
+    let mut depth = 0;
+    let mut i = 0;
+
+    let end = loop {
+        let Some(c) = at(i) else {
+            break source.len() - start;
+        };
+
+        match c {
+            '/' if at(i + 1) == Some('/') => {
+                while at(i).is_some_and(|c| c != '\n') {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if at(i + 1) == Some('*') => {
+                let mut nesting = 0;
+
+                while let Some(c) = at(i) {
+                    if c == '/' && at(i + 1) == Some('*') {
+                        nesting += 1;
+                        i += 2;
+                    } else if c == '*' && at(i + 1) == Some('/') {
+                        nesting -= 1;
+                        i += 2;
+
+                        if nesting == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            'r' if matches!(at(i + 1), Some('"') | Some('#'))
+                && !is_ident(i.checked_sub(1).and_then(at)) =>
+            {
+                let mut hashes = 0;
+                let mut j = i + 1;
+
+                while at(j) == Some('#') {
+                    hashes += 1;
+                    j += 1;
+                }
+
+                if at(j) == Some('"') {
+                    j += 1;
+
+                    while let Some(c) = at(j) {
+                        if c == '"' && (1..=hashes).all(|k| at(j + k) == Some('#')) {
+                            j += hashes;
+                            break;
+                        }
+                        j += 1;
+                    }
+
+                    i = j + 1;
+                    continue;
+                }
+            }
+            '"' => {
+                i += 1;
+
+                while let Some(c) = at(i) {
+                    match c {
+                        '\\' => i += 2,
+                        '"' => break,
+                        _ => i += 1,
+                    }
+                }
+            }
+            '\'' => {
+                if at(i + 1) == Some('\\') {
+                    i += 2;
+
+                    while at(i).is_some_and(|c| c != '\'') {
+                        i += 1;
+                    }
+                } else if at(i + 2) == Some('\'') {
+                    i += 2; // a char like 'a', otherwise a lifetime
+                }
+            }
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => {
+                depth -= 1;
+
+                if depth == 0 && c == closing {
+                    break chars[i].0 + 1;
+                }
+            }
+            ';' if depth == 0 => break chars[i].0 + 1,
+            _ => {}
+        }
+
+        i += 1;
+    };
+
+    // the rest of the line belongs to the item (e.g. a trailing comment)
+    let end = start + end;
+
+    source[end..]
+        .find('\n')
+        .map_or(source.len(), |newline| end + newline + 1)
+}
+
+fn is_ident(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Removes the indentation that all non-empty lines have in common and surrounding empty lines.
+fn dedent(code: &str) -> String {
+    let indent = code
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let lines: Vec<&str> = code
+        .lines()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect();
+
+    let first = lines
+        .iter()
+        .position(|line| !line.is_empty())
+        .unwrap_or(lines.len());
+    let last = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(first, |last| last + 1);
+
+    lines[first..last].join("\n")
+}
+
+/// Splits the C and Python code out of a Rust item.
+/// C block comments are removed from the Rust code, Python strings stay because the Rust code runs them.
+fn extract_comparisons(code: &str) -> (String, Vec<Comparison>) {
+    let mut comparisons = Vec::new();
+    let mut rust = code.to_string();
+
+    // `/* name.c` ... `*/`, other block comments stay in the Rust code
+    let mut from = 0;
+
+    while let Some(start) = rust[from..].find("/* ").map(|start| from + start) {
+        let title_end = rust[start..]
+            .find('\n')
+            .map_or(rust.len(), |end| start + end);
+        let title = rust[start + 3..title_end].trim().to_string();
+
+        let is_c = [".c", ".h", ".cpp"]
+            .iter()
+            .any(|extension| title.ends_with(extension))
+            && !title.contains(' ');
+
+        let Some(end) = rust[start..].find("*/").map(|end| start + end) else {
+            break;
+        };
+
+        if !is_c {
+            from = end + 2;
+            continue;
+        }
+
+        comparisons.push(Comparison {
+            language: Language::C,
+            title,
+            code: dedent(&rust[title_end..end]),
+        });
+
+        let line_start = rust[..start].rfind('\n').map_or(0, |newline| newline + 1);
+        let line_end = rust[end..]
+            .find('\n')
+            .map_or(rust.len(), |newline| end + newline + 1);
+
+        // and the empty line that separated the comment from the code
+        let line_end = line_end + usize::from(rust[line_end..].starts_with('\n'));
+
+        rust.replace_range(line_start..line_end, "");
+        from = line_start;
+    }
+
+    // the string literal (or `indoc!` string) passed to `py.run` or the harness (`harness/python.rs`)
+    let markers = ["py.run(", "run_python(", "run_python_with("];
+    let mut from = 0;
+
+    while let Some((index, marker)) = markers
+        .iter()
+        .filter_map(|marker| Some((from + code[from..].find(marker)?, marker)))
+        .min()
+    {
+        from = index + marker.len();
+
+        if let Some(python) = string_literal_after(&code[from..]) {
+            comparisons.push(Comparison {
+                language: Language::Python,
+                title: "Python".to_string(),
+                code: dedent(&python),
+            });
+        }
+    }
+
+    (dedent(&rust), comparisons)
+}
+
+/// The content of the first string literal in `code`, if only whitespace, brackets, comments and `indoc!` come before it.
+fn string_literal_after(code: &str) -> Option<String> {
+    let mut rest = code;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '{' || c == '(');
+
+        if let Some(after) = rest.strip_prefix("indoc!") {
+            rest = after;
+        } else if rest.starts_with("//") {
+            rest = &rest[rest.find('\n')?..];
+        } else {
+            break;
+        }
+    }
+
+    if let Some(raw) = rest.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let content = raw[hashes..].strip_prefix('"')?;
+        let end = content.find(&format!("\"{}", "#".repeat(hashes)))?;
+
+        return Some(content[..end].to_string());
+    }
+
+    let content = rest.strip_prefix('"')?;
+    let mut value = String::new();
+    let mut chars = content.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                '\n' => {} // line continuation
+                escaped => value.push(escaped),
+            },
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "union",
+    "unsafe", "use", "where", "while",
+];
+
+const C_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else",
+    "enum", "extern", "float", "for", "goto", "if", "int", "long", "return", "short", "signed",
+    "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void", "volatile",
+    "while", "bool", "class", "new", "delete", "nullptr", "template",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "break", "class", "continue", "def", "del", "elif", "else", "except",
+    "False", "finally", "for", "from", "if", "import", "in", "is", "lambda", "None", "not", "or",
+    "pass", "raise", "return", "True", "try", "while", "with", "yield",
+];
+
+/// HTML with `<span class="..">` around comments, strings, keywords, numbers, macros, attributes and lifetimes.
+fn highlight(code: &str, language: Language) -> String {
+    if language == Language::Plain {
+        return escape(code);
+    }
+
+    let keywords = match language {
+        Language::Rust => RUST_KEYWORDS,
+        Language::C => C_KEYWORDS,
+        Language::Python => PYTHON_KEYWORDS,
+        Language::Plain => &[],
+    };
+
+    let chars: Vec<char> = code.chars().collect();
+    let at = |i: usize| chars.get(i).copied();
+    let text = |from: usize, to: usize| chars[from..to.min(chars.len())].iter().collect::<String>();
+
+    let mut html = String::new();
+    let span = |html: &mut String, class: &str, content: &str| {
+        let _ = write!(html, "<span class=\"{class}\">{}</span>", escape(content));
+    };
+
+    let line_comment = match language {
+        Language::Python => "#",
+        _ => "//",
+    };
+
+    let mut i = 0;
+
+    while let Some(c) = at(i) {
+        let starts_with = |prefix: &str| {
+            prefix
+                .chars()
+                .enumerate()
+                .all(|(k, p)| at(i + k) == Some(p))
+        };
+
+        if starts_with(line_comment) {
+            let end = (i..chars.len())
+                .find(|&j| chars[j] == '\n')
+                .unwrap_or(chars.len());
+            span(&mut html, "comment", &text(i, end));
+            i = end;
+        } else if language != Language::Python && starts_with("/*") {
+            let end = (i + 2..chars.len())
+                .find(|&j| chars[j] == '*' && at(j + 1) == Some('/'))
+                .map_or(chars.len(), |j| j + 2);
+            span(&mut html, "comment", &text(i, end));
+            i = end;
+        } else if language == Language::Rust && (starts_with("#[") || starts_with("#![")) {
+            let end = (i..chars.len())
+                .find(|&j| chars[j] == ']')
+                .map_or(chars.len(), |j| j + 1);
+            span(&mut html, "attribute", &text(i, end));
+            i = end;
+        } else if language == Language::C && c == '#' {
+            let end = (i..chars.len())
+                .find(|&j| chars[j] == '\n')
+                .unwrap_or(chars.len());
+            span(&mut html, "attribute", &text(i, end));
+            i = end;
+        } else if c == '"' || (language == Language::Python && c == '\'') {
+            let triple =
+                language == Language::Python && at(i + 1) == Some(c) && at(i + 2) == Some(c);
+            let mut j = if triple { i + 3 } else { i + 1 };
+
+            while let Some(d) = at(j) {
+                if d == '\\' {
+                    j += 2;
+                } else if d == c && (!triple || (at(j + 1) == Some(c) && at(j + 2) == Some(c))) {
+                    j += if triple { 3 } else { 1 };
+                    break;
+                } else {
+                    j += 1;
+                }
+            }
+
+            span(&mut html, "string", &text(i, j));
+            i = j;
+        } else if c == '\'' && language != Language::Python {
+            if at(i + 1) == Some('\\') || at(i + 2) == Some('\'') {
+                let end = (i + 2..chars.len())
+                    .find(|&j| chars[j] == '\'')
+                    .map_or(chars.len(), |j| j + 1);
+                span(&mut html, "string", &text(i, end));
+                i = end;
+            } else {
+                let end = (i + 1..chars.len())
+                    .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'))
+                    .unwrap_or(chars.len());
+                span(&mut html, "lifetime", &text(i, end));
+                i = end;
+            }
+        } else if c.is_ascii_digit() && !is_ident(i.checked_sub(1).and_then(at)) {
+            let end = (i..chars.len())
+                .find(|&j| {
+                    !(chars[j].is_alphanumeric()
+                        || chars[j] == '_'
+                        || chars[j] == '.' && at(j + 1).is_some_and(|d| d.is_ascii_digit()))
+                })
+                .unwrap_or(chars.len());
+            span(&mut html, "number", &text(i, end));
+            i = end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = (i..chars.len())
+                .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'))
+                .unwrap_or(chars.len());
+            let word = text(i, end);
+
+            if keywords.contains(&word.as_str()) {
+                span(&mut html, "keyword", &word);
+            } else if language == Language::Rust && at(end) == Some('!') {
+                span(&mut html, "macro", &format!("{word}!"));
+                i = end + 1;
+                continue;
+            } else {
+                html.push_str(&escape(&word));
+            }
+
+            i = end;
+        } else {
+            html.push_str(&escape(&c.to_string()));
+            i += 1;
+        }
+    }
+
+    html
+}
+
+/// Renders documentation lines: paragraphs, `-` lists, code blocks (with their language and doctest attributes),
+/// inline code, links to other chapters (``[`name`]``) and bare URLs.
+fn render_doc(lines: &[String], chapters: &[String]) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut list: Vec<String> = Vec::new();
+    let mut index = 0;
+
+    let flush = |html: &mut String, paragraph: &mut Vec<String>, list: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            let _ = writeln!(html, "<p>{}</p>", paragraph.join("\n"));
+            paragraph.clear();
+        }
+
+        if !list.is_empty() {
+            let items: Vec<String> = list.iter().map(|item| format!("<li>{item}</li>")).collect();
+            let _ = writeln!(html, "<ul>{}</ul>", items.join(""));
+            list.clear();
+        }
+    };
+
+    while index < lines.len() {
+        let line = lines[index].trim_end();
+
+        if let Some(info) = line.trim_start().strip_prefix("```") {
+            flush(&mut html, &mut paragraph, &mut list);
+
+            let end = (index + 1..lines.len())
+                .find(|&j| lines[j].trim_start().starts_with("```"))
+                .unwrap_or(lines.len());
+
+            // hidden doctest lines start with `# `
+            let code: Vec<&str> = lines[index + 1..end]
+                .iter()
+                .map(String::as_str)
+                .filter(|line| !(line.trim_start().starts_with("# ") || line.trim() == "#"))
+                .collect();
+
+            html.push_str(&render_code_block(&code.join("\n"), info));
+            index = end + 1;
+            continue;
+        }
+
+        // `- item` or `1. item`
+        let item = line.strip_prefix("- ").or_else(|| {
+            let (number, item) = line.split_once(". ")?;
+            number.parse::<u32>().is_ok().then_some(item)
+        });
+
+        if line.trim().is_empty() {
+            flush(&mut html, &mut paragraph, &mut list);
+        } else if let Some(item) = item {
+            if !paragraph.is_empty() {
+                flush(&mut html, &mut paragraph, &mut Vec::new());
+            }
+            list.push(render_inline(item, chapters));
+        } else if let Some(heading) = line.strip_prefix("# ") {
+            flush(&mut html, &mut paragraph, &mut list);
+            let _ = writeln!(html, "<h3>{}</h3>", render_inline(heading, chapters));
+        } else {
+            paragraph.push(render_inline(line, chapters));
+        }
+
+        index += 1;
+    }
+
+    flush(&mut html, &mut paragraph, &mut list);
+
+    html
+}
+
+fn render_code_block(code: &str, info: &str) -> String {
+    let attributes: Vec<&str> = info
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    let language = match attributes.first().copied() {
+        None | Some("rust") | Some("compile_fail") | Some("should_panic") | Some("no_run") => {
+            Language::Rust
+        }
+        Some("c") | Some("cpp") => Language::C,
+        Some("python") => Language::Python,
+        _ => Language::Plain,
+    };
+
+    let badge = if let Some(index) = attributes.iter().position(|a| *a == "compile_fail") {
+        match attributes.get(index + 1) {
+            Some(code) if code.starts_with('E') => format!("does not compile ({code})"),
+            _ => "does not compile".to_string(),
+        }
+    } else if attributes.contains(&"should_panic") {
+        "panics".to_string()
+    } else {
+        String::new()
+    };
+
+    let badge = if badge.is_empty() {
+        String::new()
+    } else {
+        format!("<div class=\"badge fail\">{badge}</div>")
+    };
+
+    format!(
+        "<div class=\"code\">{badge}<pre><code>{}</code></pre></div>\n",
+        highlight(code, language)
+    )
+}
+
+/// Inline markup of a documentation line.
+fn render_inline(line: &str, chapters: &[String]) -> String {
+    let mut html = String::new();
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        // ``[`name`]`` or ``[`name`](mod@name)``
+        if let Some(link) = rest.strip_prefix("[`") {
+            if let Some(end) = link.find("`]") {
+                let name = &link[..end];
+                let mut after = &link[end + 2..];
+
+                if after.starts_with('(') {
+                    after = after.find(')').map_or(after, |close| &after[close + 1..]);
+                }
+
+                if chapters.iter().any(|chapter| chapter == name) {
+                    let _ = write!(
+                        html,
+                        "<a href=\"{name}.html\"><code>{}</code></a>",
+                        escape(name)
+                    );
+                } else {
+                    let _ = write!(html, "<code>{}</code>", escape(name));
+                }
+
+                rest = after;
+                continue;
+            }
+        }
+
+        if let Some(code) = rest.strip_prefix('`') {
+            if let Some(end) = code.find('`') {
+                let _ = write!(html, "<code>{}</code>", escape(&code[..end]));
+                rest = &code[end + 1..];
+                continue;
+            }
+        }
+
+        if rest.starts_with("https://") || rest.starts_with("http://") {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '>' || c == ')')
+                .unwrap_or(rest.len());
+            let url = &rest[..end];
+            let _ = write!(html, "<a href=\"{0}\">{0}</a>", escape(url));
+            rest = &rest[end..];
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap();
+        html.push_str(&escape(&c.to_string()));
+        rest = &rest[c.len_utf8()..];
+    }
+
+    html
+}
+
+fn page(title: &str, navigation: &str, content: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n<nav>\n{navigation}</nav>\n<main>\n{content}</main>\n</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+fn navigation(chapters: &[Chapter], current: Option<&str>) -> String {
+    let mut html =
+        String::from("<a href=\"index.html\"><strong>Rust Snippets</strong></a>\n<ol>\n");
+
+    for chapter in chapters {
+        let class = if Some(chapter.name.as_str()) == current {
+            " class=\"current\""
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            html,
+            "<li><a href=\"{0}.html\"{class}>{0}</a></li>",
+            chapter.name
+        );
+    }
+
+    html.push_str("</ol>\n");
+    html
+}
+
+fn render_index(doc: &[String], chapters: &[Chapter]) -> String {
+    let names: Vec<String> = chapters
+        .iter()
+        .map(|chapter| chapter.name.clone())
+        .collect();
+
+    let content = format!("<h1>Rust Snippets</h1>\n{}", render_doc(doc, &names));
+
+    page("Rust Snippets", &navigation(chapters, None), &content)
+}
+
+fn render_chapter(chapter: &Chapter, chapters: &[Chapter]) -> String {
+    let names: Vec<String> = chapters
+        .iter()
+        .map(|chapter| chapter.name.clone())
+        .collect();
+
+    let mut content = format!(
+        "<h1>{}</h1>\n{}",
+        escape(&chapter.name),
+        render_doc(&chapter.doc, &names)
+    );
+
+    if !chapter.sections.is_empty() {
+        content.push_str("<ul class=\"contents\">\n");
+
+        for section in &chapter.sections {
+            let _ = writeln!(
+                content,
+                "<li><a href=\"#{0}\">{0}</a></li>",
+                escape(&section.name)
+            );
+        }
+
+        content.push_str("</ul>\n");
+    }
+
+    for section in &chapter.sections {
+        let _ = writeln!(
+            content,
+            "<section id=\"{0}\">\n<h2>{0}</h2>",
+            escape(&section.name)
+        );
+
+        for attribute in &section.attributes {
+            if let Some(feature) = attribute
+                .split("feature = \"")
+                .nth(1)
+                .and_then(|rest| rest.split('"').next())
+            {
+                let _ = writeln!(
+                    content,
+                    "<div class=\"badge\">--features {}</div>",
+                    escape(feature)
+                );
+            }
+        }
+
+        content.push_str(&render_doc(&section.doc, &names));
+
+        let rust = format!(
+            "<pre><code>{}</code></pre>",
+            highlight(&section.code, Language::Rust)
+        );
+
+        if section.comparisons.is_empty() {
+            let _ = writeln!(content, "<div class=\"code\">{rust}</div>");
+        } else {
+            content.push_str("<div class=\"compare\">\n");
+            let _ = writeln!(
+                content,
+                "<div class=\"code\"><div class=\"title\">Rust</div>{rust}</div>"
+            );
+
+            for comparison in &section.comparisons {
+                let _ = writeln!(
+                    content,
+                    "<div class=\"code\"><div class=\"title\">{}</div><pre><code>{}</code></pre></div>",
+                    escape(&comparison.title),
+                    highlight(&comparison.code, comparison.language)
+                );
+            }
+
+            content.push_str("</div>\n");
+        }
+
+        content.push_str("</section>\n");
+    }
+
+    page(
+        &chapter.name,
+        &navigation(chapters, Some(&chapter.name)),
+        &content,
+    )
+}
+
+const STYLE: &str = "\
+body { margin: 0; display: flex; font-family: sans-serif; line-height: 1.5; color: #222; }
+nav { width: 14em; flex-shrink: 0; padding: 1em; background: #f4f4f4; min-height: 100vh; box-sizing: border-box; }
+nav ol { padding-left: 1.5em; }
+nav a { color: #333; text-decoration: none; }
+nav a.current { font-weight: bold; color: #b7410e; }
+main { padding: 1em 2em; max-width: 75em; min-width: 0; }
+h1 { color: #b7410e; }
+section { border-top: 1px solid #ddd; margin-top: 2em; }
+pre { background: #fafafa; border: 1px solid #e4e4e4; padding: 0.75em; overflow-x: auto; margin: 0; }
+code { font-family: monospace; font-size: 0.9em; }
+p code, li code { background: #f0f0f0; padding: 0 0.2em; }
+.code { margin: 0.75em 0; min-width: 0; }
+.compare { display: flex; gap: 1em; }
+.compare .code { flex: 1; }
+.title { font-weight: bold; font-size: 0.85em; color: #555; }
+.badge { display: inline-block; font-size: 0.8em; background: #e8eef7; padding: 0 0.5em; border-radius: 0.3em; }
+.badge.fail { background: #fbe3e3; }
+.comment { color: #6a737d; font-style: italic; }
+.string { color: #22863a; }
+.keyword { color: #a626a4; font-weight: bold; }
+.number { color: #986801; }
+.macro { color: #4078f2; }
+.attribute { color: #0184bc; }
+.lifetime { color: #c18401; }
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        let source = "//! Intro\n//!\n//! More\n\n/// Docs\n#[test]\nfn closure() {\n    let s = \"}\"; // }\n    let c = '{';\n}\n\n#[cfg(test)]\nfn helper() {}\n\nfn last<'a>(x: &'a str) -> &'a str { x }\n";
+
+        let chapter = parse_chapter("functions", source);
+
+        assert_eq!(chapter.doc, ["Intro", "", "More"]);
+        assert_eq!(chapter.sections.len(), 2); // the `#[cfg(test)]` helper is skipped
+        assert_eq!(chapter.sections[0].name, "closure");
+        assert_eq!(chapter.sections[0].doc, ["Docs"]);
+        assert_eq!(chapter.sections[0].attributes, ["#[test]"]);
+        assert_eq!(
+            chapter.sections[0].code,
+            "fn closure() {\n    let s = \"}\"; // }\n    let c = '{';\n}"
+        );
+        assert_eq!(chapter.sections[1].name, "last");
+    }
+
+    #[test]
+    fn comparisons() {
+        let code = "fn c() {\n    /* main.c\n\n    int main() {}\n\n    */\n\n    let x = 1;\n    py.run(indoc! { // comment\n        \"\n        x = 1\n        \"\n    });\n}";
+
+        let (rust, comparisons) = extract_comparisons(code);
+
+        assert_eq!(rust, "fn c() {\n    let x = 1;\n    py.run(indoc! { // comment\n        \"\n        x = 1\n        \"\n    });\n}");
+        assert_eq!(
+            comparisons,
+            [
+                Comparison {
+                    language: Language::C,
+                    title: "main.c".to_string(),
+                    code: "int main() {}".to_string()
+                },
+                Comparison {
+                    language: Language::Python,
+                    title: "Python".to_string(),
+                    code: "x = 1".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn comparisons_after_other_comments() {
+        let code = "fn c() {\n    /* not a comparison */\n    let x = 1;\n    /* main.c\n\n    int x = 1;\n\n    */\n}";
+
+        let (rust, comparisons) = extract_comparisons(code);
+
+        assert_eq!(
+            rust,
+            "fn c() {\n    /* not a comparison */\n    let x = 1;\n}"
+        );
+        assert_eq!(
+            comparisons,
+            [Comparison {
+                language: Language::C,
+                title: "main.c".to_string(),
+                code: "int x = 1;".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn highlighting() {
+        assert_eq!(
+            highlight("let x = \"<\"; // x", Language::Rust),
+            "<span class=\"keyword\">let</span> x = <span class=\"string\">&quot;&lt;&quot;</span>; <span class=\"comment\">// x</span>"
+        );
+        assert_eq!(
+            highlight("vec![1]", Language::Rust),
+            "<span class=\"macro\">vec!</span>[<span class=\"number\">1</span>]"
+        );
+        assert_eq!(
+            highlight("def f(): # x", Language::Python),
+            "<span class=\"keyword\">def</span> f(): <span class=\"comment\"># x</span>"
+        );
+    }
+
+    #[test]
+    fn documentation() {
+        let lines: Vec<String> = [
+            "See [`traits`] and `x`:",
+            "",
+            "```compile_fail,E0308",
+            "let x: i32 = \"\";",
+            "```",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        let html = render_doc(&lines, &["traits".to_string()]);
+
+        assert!(html.starts_with(
+            "<p>See <a href=\"traits.html\"><code>traits</code></a> and <code>x</code>:</p>"
+        ));
+        assert!(html.contains("does not compile (E0308)"));
+    }
+}
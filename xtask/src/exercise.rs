@@ -0,0 +1,459 @@
+//! Exercises for the lessons: `cargo xtask exercise`.
+//!
+//! Every file in `exercises/<lesson>/` is a small test that doesn't compile, panics with `todo!()` or fails an assertion.
+//! An exercise is solved when its tests pass. The exercises are standalone files compiled with `rustc --test`,
+//! so they don't need the workspace and can't break `cargo test`.
+//!
+//! - `cargo xtask exercise` (or `watch`): runs the first unsolved exercise and re-runs an exercise whenever its file changes.
+//!   Type `hint` for the next hint, `list` for the progress and `quit` to stop.
+//! - `cargo xtask exercise list`: all exercises and whether they are solved
+//! - `cargo xtask exercise run <name>`: runs one exercise, e.g. `variables/mutability` or just `mutability`
+//! - `cargo xtask exercise hint <name>`: shows the next hint of an exercise
+//!
+//! `exercises/hints.md` lists the exercises in order with their hints (one `## lesson/name` section each).
+//! The progress (solved exercises and the number of hints shown) is stored in `exercises/.progress`, which is not committed.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, PartialEq)]
+struct Exercise {
+    name: String,
+    hints: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Entry {
+    solved: bool,
+    hints: usize,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Progress {
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Progress {
+    fn parse(text: &str) -> Self {
+        let mut entries = BTreeMap::new();
+
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let mut words = line.split_whitespace();
+
+            let Some(name) = words.next() else {
+                continue;
+            };
+
+            let mut entry = Entry::default();
+
+            for word in words {
+                if word == "solved" {
+                    entry.solved = true;
+                } else if let Some(hints) = word.strip_prefix("hints=") {
+                    entry.hints = hints.parse().unwrap_or(0);
+                }
+            }
+
+            entries.insert(name.to_string(), entry);
+        }
+
+        Self { entries }
+    }
+
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        let mut text =
+            String::from("# progress of `cargo xtask exercise`, delete this file to start over\n");
+
+        for (name, entry) in &self.entries {
+            text.push_str(name);
+            text.push_str(&format!(" hints={}", entry.hints));
+
+            if entry.solved {
+                text.push_str(" solved");
+            }
+
+            text.push('\n');
+        }
+
+        if let Err(error) = std::fs::write(path, text) {
+            eprintln!("{} could not be written: {error}", path.display());
+        }
+    }
+
+    fn solved(&self, name: &str) -> bool {
+        self.entries.get(name).is_some_and(|entry| entry.solved)
+    }
+}
+
+/// The exercises in the order of the `## lesson/name` sections in `hints.md`, the `- ` lines of a section are its hints.
+fn parse_hints(text: &str) -> Vec<Exercise> {
+    let mut exercises: Vec<Exercise> = Vec::new();
+
+    for line in text.lines() {
+        if let Some(name) = line.strip_prefix("## ") {
+            exercises.push(Exercise {
+                name: name.trim().to_string(),
+                hints: Vec::new(),
+            });
+        } else if let (Some(hint), Some(exercise)) = (line.strip_prefix("- "), exercises.last_mut())
+        {
+            exercise.hints.push(hint.to_string());
+        }
+    }
+
+    exercises
+}
+
+struct Exercises {
+    directory: PathBuf,
+    build: PathBuf,
+    list: Vec<Exercise>,
+    progress: Progress,
+}
+
+impl Exercises {
+    fn path(&self, exercise: &Exercise) -> PathBuf {
+        self.directory.join(format!("{}.rs", exercise.name))
+    }
+
+    fn progress_path(&self) -> PathBuf {
+        self.directory.join(".progress")
+    }
+
+    /// `variables/mutability` or only `mutability` if no other exercise has that name.
+    fn find(&self, name: &str) -> Result<usize, String> {
+        let matches: Vec<usize> = (0..self.list.len())
+            .filter(|&index| {
+                let exercise = &self.list[index].name;
+                exercise == name || exercise.rsplit('/').next() == Some(name)
+            })
+            .collect();
+
+        match matches[..] {
+            [index] => Ok(index),
+            [] => Err(format!(
+                "unknown exercise `{name}`, see `cargo xtask exercise list`"
+            )),
+            _ => Err(format!("`{name}` is ambiguous, use `<lesson>/{name}`")),
+        }
+    }
+
+    fn first_unsolved(&self) -> Option<usize> {
+        (0..self.list.len()).find(|&index| !self.progress.solved(&self.list[index].name))
+    }
+
+    /// Compiles and runs the tests of the exercise (compiler and test output go to the terminal) and records the result.
+    fn run(&mut self, index: usize) -> bool {
+        let exercise = &self.list[index];
+        let executable = self.build.join(exercise.name.replace('/', "_"));
+
+        println!("--> {}\n", exercise.name);
+
+        let compiled = Command::new(std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+            .args(["--edition", "2021", "--test", "--color", "always"])
+            .arg(self.path(exercise))
+            .arg("-o")
+            .arg(&executable)
+            .status()
+            .is_ok_and(|status| status.success());
+
+        let passed = compiled
+            && Command::new(&executable)
+                .args(["--color", "always"])
+                .status()
+                .is_ok_and(|status| status.success());
+
+        let entry = self
+            .progress
+            .entries
+            .entry(exercise.name.clone())
+            .or_default();
+        entry.solved = passed;
+
+        self.progress.save(&self.progress_path());
+
+        match (compiled, passed) {
+            (false, _) => println!("\n{} doesn't compile yet.", exercise.name),
+            (true, false) => println!("\nThe tests of {} fail.", exercise.name),
+            (true, true) => println!("\n{} is solved!", exercise.name),
+        }
+
+        passed
+    }
+
+    /// Shows the hints up to the next one that wasn't shown yet.
+    fn hint(&mut self, index: usize) {
+        let exercise = &self.list[index];
+        let entry = self
+            .progress
+            .entries
+            .entry(exercise.name.clone())
+            .or_default();
+
+        entry.hints = (entry.hints + 1).min(exercise.hints.len());
+
+        for (number, hint) in exercise.hints.iter().take(entry.hints).enumerate() {
+            println!("hint {}/{}: {hint}", number + 1, exercise.hints.len());
+        }
+
+        if entry.hints == exercise.hints.len() {
+            println!("(that was the last hint)");
+        }
+
+        self.progress.save(&self.progress_path());
+    }
+
+    fn print_list(&self) {
+        for exercise in &self.list {
+            let status = if self.progress.solved(&exercise.name) {
+                "solved"
+            } else {
+                "-"
+            };
+
+            println!("  {:<32} {status}", exercise.name);
+        }
+
+        let solved = self
+            .list
+            .iter()
+            .filter(|exercise| self.progress.solved(&exercise.name))
+            .count();
+
+        println!("\n{solved}/{} solved", self.list.len());
+    }
+
+    /// Runs the current exercise, then re-runs every exercise whose file changes.
+    /// Solving the current exercise moves on to the next unsolved one.
+    fn watch(&mut self) -> ExitCode {
+        let Some(mut current) = self.first_unsolved() else {
+            println!("All exercises are solved!");
+            return ExitCode::SUCCESS;
+        };
+
+        // commands are read on another thread, so that the files can be polled in between
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut modified = self.modification_times();
+
+        self.run_and_prompt(&mut current);
+
+        loop {
+            match receiver.recv_timeout(Duration::from_millis(500)) {
+                Ok(command) => match command.trim() {
+                    "h" | "hint" => self.hint(current),
+                    "l" | "list" => self.print_list(),
+                    "r" | "run" | "" => self.run_and_prompt(&mut current),
+                    "q" | "quit" => return ExitCode::SUCCESS,
+                    other => {
+                        println!("unknown command `{other}`, expected hint, list, run or quit")
+                    }
+                },
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    std::thread::sleep(Duration::from_millis(500))
+                } // no stdin
+            }
+
+            let now = self.modification_times();
+
+            if let Some(changed) = (0..self.list.len()).find(|&index| now[index] != modified[index])
+            {
+                current = changed;
+                self.run_and_prompt(&mut current);
+            }
+
+            modified = now;
+
+            if self.first_unsolved().is_none() {
+                println!("All exercises are solved!");
+                return ExitCode::SUCCESS;
+            }
+        }
+    }
+
+    fn run_and_prompt(&mut self, current: &mut usize) {
+        print!("\x1b[2J\x1b[H"); // clear the terminal
+
+        if self.run(*current) {
+            match self.first_unsolved() {
+                Some(next) => {
+                    *current = next;
+                    println!("Next: {}", self.path(&self.list[next]).display());
+                }
+                None => return,
+            }
+        } else {
+            println!(
+                "Edit {} and save it to run it again.",
+                self.path(&self.list[*current]).display()
+            );
+        }
+
+        println!("Commands: hint, list, run, quit");
+    }
+
+    fn modification_times(&self) -> Vec<Option<SystemTime>> {
+        self.list
+            .iter()
+            .map(|exercise| {
+                std::fs::metadata(self.path(exercise))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+pub fn main(root: &Path, target: &Path, args: &[String]) -> ExitCode {
+    let directory = root.join("exercises");
+
+    let list = match std::fs::read_to_string(directory.join("hints.md")) {
+        Ok(text) => parse_hints(&text),
+        Err(error) => {
+            eprintln!("exercises/hints.md could not be read: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let build = target.join("exercises");
+
+    if let Err(error) = std::fs::create_dir_all(&build) {
+        eprintln!("{} could not be created: {error}", build.display());
+        return ExitCode::FAILURE;
+    }
+
+    let mut exercises = Exercises {
+        progress: Progress::load(&directory.join(".progress")),
+        directory,
+        build,
+        list,
+    };
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let found = |name: &str, exercises: &Exercises| {
+        exercises.find(name).map_err(|error| {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        })
+    };
+
+    match args[..] {
+        [] | ["watch"] => exercises.watch(),
+        ["list"] => {
+            exercises.print_list();
+            ExitCode::SUCCESS
+        }
+        ["run", name] => match found(name, &exercises) {
+            Ok(index) if exercises.run(index) => ExitCode::SUCCESS,
+            Ok(_) => ExitCode::FAILURE,
+            Err(code) => code,
+        },
+        ["hint", name] => match found(name, &exercises) {
+            Ok(index) => {
+                exercises.hint(index);
+                ExitCode::SUCCESS
+            }
+            Err(code) => code,
+        },
+        _ => {
+            eprintln!("usage: cargo xtask exercise [watch | list | run <name> | hint <name>]");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints() {
+        let exercises = parse_hints("# Hints\n\n- not a hint\n\n## variables/mutability\n\n- first\n- second\n\n## matching/match_enum\n- only\n");
+
+        assert_eq!(
+            exercises,
+            [
+                Exercise {
+                    name: "variables/mutability".to_string(),
+                    hints: vec!["first".to_string(), "second".to_string()],
+                },
+                Exercise {
+                    name: "matching/match_enum".to_string(),
+                    hints: vec!["only".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn progress() {
+        let progress = Progress::parse(
+            "# comment\nvariables/mutability hints=2 solved\nmatching/match_enum hints=1\n",
+        );
+
+        assert!(progress.solved("variables/mutability"));
+        assert!(!progress.solved("matching/match_enum"));
+        assert!(!progress.solved("matching/match_option"));
+        assert_eq!(progress.entries["matching/match_enum"].hints, 1);
+    }
+
+    /// Every section of hints.md has a file and hints, every file has a section.
+    #[test]
+    fn every_exercise_has_hints() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../exercises");
+        let exercises = parse_hints(&std::fs::read_to_string(directory.join("hints.md")).unwrap());
+
+        for exercise in &exercises {
+            assert!(
+                directory.join(format!("{}.rs", exercise.name)).is_file(),
+                "{} has no file",
+                exercise.name
+            );
+            assert!(!exercise.hints.is_empty(), "{} has no hints", exercise.name);
+        }
+
+        for lesson in std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+        {
+            if !lesson.is_dir() {
+                continue;
+            }
+
+            for file in std::fs::read_dir(&lesson)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+            {
+                let name = format!(
+                    "{}/{}",
+                    lesson.file_name().unwrap().to_string_lossy(),
+                    file.file_stem().unwrap().to_string_lossy()
+                );
+
+                assert!(
+                    exercises.iter().any(|exercise| exercise.name == name),
+                    "{name} is missing in hints.md"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,480 @@
+//! Builds and tests the whole workspace with one command: `cargo xtask` (alias in `.cargo/config.toml`).
+//!
+//! - `build`: builds every member of the workspace
+//! - `test`: runs the Rust tests of the workspace
+//! - `c`: compiles `rustinc/rustinc.c` against the `rustinc` static library and checks its output
+//! - `python`: runs the tests of `snippets` with the `python` feature (pyo3 and numpy)
+//! - `miri`: runs the tests of the `unsafe_rust` lesson under Miri, which fails on undefined behaviour
+//! - `features`: builds and tests `snippets` with every combination of its features and reports which lessons run in each
+//! - `quizzes`: checks the answers of the quizzes in `quizzes/` by compiling and running their code (see `quiz.rs`)
+//! - `book`: renders the lessons as a static HTML site in `target/book` (see `book.rs`)
+//!
+//! `cargo xtask` runs all of them except `features` (which repeats `test` and `python`) and `book`, `cargo xtask test c` only the given ones.
+//! Steps whose tools are missing (a C compiler, Python, Miri) are skipped instead of failing.
+//! A summary of all steps is printed at the end, the exit code is non-zero if any step failed.
+//!
+//! `cargo xtask exercise` and `cargo xtask quiz` are not steps but the interactive exercise runner (see `exercise.rs`)
+//! and the quizzes (see `quiz.rs`).
+
+mod book;
+mod exercise;
+mod quiz;
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::time::{Duration, Instant};
+
+/// The packages of the workspace (`snippets` is the package `rust_snippets`).
+const PACKAGES: [&str; 4] = [
+    "rust_snippets",
+    "snippets_derive",
+    "rustinc",
+    "minimal_example",
+];
+
+type Step = fn(&Path) -> Outcome;
+
+/// The name, the step and whether it runs without being named.
+const STEPS: [(&str, Step, bool); 8] = [
+    ("build", build, true),
+    ("test", test, true),
+    ("c", c, true),
+    ("python", python, true),
+    ("miri", miri, true),
+    ("quizzes", quizzes, true),
+    ("features", features, false),
+    ("book", book, false),
+];
+
+enum Outcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.first().is_some_and(|arg| arg == "exercise") {
+        let root = workspace_root();
+        return exercise::main(&root, &target_dir(&root), &args[1..]);
+    }
+
+    if args.first().is_some_and(|arg| arg == "quiz") {
+        return quiz::main(&workspace_root(), &args[1..]);
+    }
+
+    if let Some(unknown) = args
+        .iter()
+        .find(|arg| !STEPS.iter().any(|(name, _, _)| name == arg))
+    {
+        let names: Vec<&str> = STEPS.iter().map(|(name, _, _)| *name).collect();
+        eprintln!(
+            "unknown step `{unknown}`, expected one of: {}",
+            names.join(", ")
+        );
+        return ExitCode::FAILURE;
+    }
+
+    let root = workspace_root();
+
+    let mut summary = Vec::new();
+
+    for (name, step, default) in STEPS {
+        let selected = if args.is_empty() {
+            default
+        } else {
+            args.iter().any(|arg| arg == name)
+        };
+
+        if !selected {
+            continue;
+        }
+
+        println!("\n==> {name}");
+
+        let start = Instant::now();
+        let outcome = step(&root);
+
+        summary.push((name, outcome, start.elapsed()));
+    }
+
+    println!("\n{}", format_summary(&summary));
+
+    if summary
+        .iter()
+        .any(|(_, outcome, _)| matches!(outcome, Outcome::Failed(_)))
+    {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is a member of the workspace")
+        .to_path_buf()
+}
+
+fn target_dir(root: &Path) -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.join("target"))
+}
+
+fn cargo() -> Command {
+    Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+}
+
+/// Runs the command with inherited stdout/stderr and turns a non-zero exit into a failure.
+fn run(command: &mut Command) -> Outcome {
+    match command.status() {
+        Ok(status) if status.success() => Outcome::Passed,
+        Ok(status) => Outcome::Failed(format!("{:?} exited with {status}", command.get_program())),
+        Err(error) => Outcome::Failed(format!(
+            "{:?} could not be started: {error}",
+            command.get_program()
+        )),
+    }
+}
+
+/// Whether `program` can be started at all.
+fn available(program: &str) -> bool {
+    Command::new(program).arg("--version").output().is_ok()
+}
+
+fn build(root: &Path) -> Outcome {
+    for package in PACKAGES {
+        if let outcome @ Outcome::Failed(_) =
+            run(cargo()
+                .current_dir(root)
+                .args(["build", "--package", package]))
+        {
+            return outcome;
+        }
+    }
+
+    Outcome::Passed
+}
+
+fn test(root: &Path) -> Outcome {
+    run(cargo()
+        .current_dir(root)
+        .args(["test", "--workspace", "--exclude", "xtask"]))
+}
+
+/// `rustinc.c` calls `addr` from the `rustinc` static library and prints `Result: 30`.
+fn c(root: &Path) -> Outcome {
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    if !available(&compiler) {
+        return Outcome::Skipped(format!("no C compiler (`{compiler}`) found"));
+    }
+
+    if let outcome @ Outcome::Failed(_) =
+        run(cargo()
+            .current_dir(root)
+            .args(["build", "--package", "rustinc"]))
+    {
+        return outcome;
+    }
+
+    let target = target_dir(root);
+    let library = target.join("debug").join("librustinc.a");
+    let executable = target.join("debug").join("rustinc_c");
+
+    let mut compile = Command::new(&compiler);
+    compile
+        .arg(root.join("rustinc").join("rustinc.c"))
+        .arg(&library)
+        .arg("-o")
+        .arg(&executable);
+
+    // the Rust standard library inside of the static library needs these system libraries
+    if cfg!(target_os = "linux") {
+        compile.args(["-lpthread", "-ldl", "-lm"]);
+    }
+
+    if let outcome @ Outcome::Failed(_) = run(&mut compile) {
+        return outcome;
+    }
+
+    let output = match Command::new(&executable).output() {
+        Ok(output) => output,
+        Err(error) => {
+            return Outcome::Failed(format!("{executable:?} could not be started: {error}"))
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    print!("{stdout}");
+
+    if stdout.trim() == "Result: 30" {
+        Outcome::Passed
+    } else {
+        Outcome::Failed(format!("expected `Result: 30`, got `{}`", stdout.trim()))
+    }
+}
+
+/// The Python tests are the Rust tests behind the `python` feature, they embed a Python interpreter and link libpython.
+/// `python` doesn't turn on pyo3's `extension-module` (that's `python-extension`), otherwise the test binaries wouldn't link.
+/// pyo3 finds the interpreter in `PYO3_PYTHON` or on the `PATH`, so that's the one that has to exist.
+fn python(root: &Path) -> Outcome {
+    let interpreter = std::env::var("PYO3_PYTHON").ok();
+
+    let found = match &interpreter {
+        Some(interpreter) => available(interpreter),
+        None => ["python3", "python"].into_iter().any(available),
+    };
+
+    if !found {
+        return Outcome::Skipped(match interpreter {
+            Some(interpreter) => format!("PYO3_PYTHON `{interpreter}` can't be started"),
+            None => "no Python interpreter found".to_string(),
+        });
+    }
+
+    run(cargo().current_dir(root).args([
+        "test",
+        "--package",
+        "rust_snippets",
+        "--features",
+        "python",
+    ]))
+}
+
+/// The `unsafe_rust` tests exercise the `// SAFETY:` invariants of its safe abstractions, Miri checks that they hold.
+/// Miri is found like in `ub_lab.rs`: for the active toolchain or for nightly (`rustup +nightly component add miri`).
+/// `cargo` is started through rustup here (not `$CARGO`), `+nightly` is a rustup argument.
+fn miri(root: &Path) -> Outcome {
+    let candidates: [&[&str]; 2] = [&["miri"], &["+nightly", "miri"]];
+
+    let Some(args) = candidates.into_iter().find(|args| {
+        Command::new("cargo")
+            .args(*args)
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }) else {
+        return Outcome::Skipped(
+            "Miri is not installed (rustup +nightly component add miri)".to_string(),
+        );
+    };
+
+    run(Command::new("cargo").current_dir(root).args(args).args([
+        "test",
+        "--package",
+        "rust_snippets",
+        "unsafe_rust",
+    ]))
+}
+
+/// Builds and tests `rust_snippets` with every subset of its features.
+/// A combination can fail to build (e.g. build.rs using a build-dependency the feature doesn't enable) or fail its tests.
+fn features(root: &Path) -> Outcome {
+    let manifest = match std::fs::read_to_string(root.join("snippets").join("Cargo.toml")) {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            return Outcome::Failed(format!("snippets/Cargo.toml could not be read: {error}"))
+        }
+    };
+
+    let names = feature_names(&manifest);
+
+    let mut failed = Vec::new();
+    let mut report = Vec::new();
+
+    for combination in combinations(&names) {
+        let label = if combination.is_empty() {
+            "(none)".to_string()
+        } else {
+            combination.join(",")
+        };
+
+        println!("\n--> features: {label}");
+
+        let output = cargo()
+            .current_dir(root)
+            .args([
+                "test",
+                "--package",
+                "rust_snippets",
+                "--no-default-features",
+            ])
+            .args(["--features", &combination.join(",")])
+            .output();
+
+        let output = match output {
+            Ok(output) => output,
+            Err(error) => return Outcome::Failed(format!("cargo could not be started: {error}")),
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let results = test_results(&stdout);
+
+        let status = if results.is_empty() && !output.status.success() {
+            print!("{}", String::from_utf8_lossy(&output.stderr));
+            "build FAILED".to_string()
+        } else {
+            let failures: Vec<&str> = results
+                .iter()
+                .filter(|(_, result)| *result == "FAILED")
+                .map(|(name, _)| *name)
+                .collect();
+
+            if failures.is_empty() && output.status.success() {
+                "ok".to_string()
+            } else if failures.is_empty() {
+                "FAILED: a test binary exited abnormally".to_string()
+            } else {
+                format!("FAILED: {}", failures.join(", "))
+            }
+        };
+
+        if status != "ok" {
+            failed.push(label.clone());
+        }
+
+        report.push(format!("  {label:<12} {status}"));
+
+        if !results.is_empty() {
+            report.push(lessons(&results));
+        }
+    }
+
+    println!("\nfeature matrix:\n{}", report.join("\n"));
+
+    if failed.is_empty() {
+        Outcome::Passed
+    } else {
+        Outcome::Failed(format!("failing combinations: {}", failed.join(" ")))
+    }
+}
+
+/// Features that only change how the crate is built for another consumer, the tests can't run with them.
+/// `python-extension` builds the Python module: pyo3 doesn't link libpython then, so the test binaries don't link.
+const BUILD_ONLY_FEATURES: [&str; 1] = ["python-extension"];
+
+/// The names in the `[features]` table, without `default` and the build-only features.
+fn feature_names(manifest: &str) -> Vec<String> {
+    manifest
+        .lines()
+        .skip_while(|line| line.trim() != "[features]")
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, _)| name.trim().to_string())
+        .filter(|name| !name.is_empty() && !name.starts_with('#') && name != "default")
+        .filter(|name| !BUILD_ONLY_FEATURES.contains(&name.as_str()))
+        .collect()
+}
+
+/// Every subset of `names`, from none to all.
+fn combinations(names: &[String]) -> Vec<Vec<String>> {
+    (0..1u32 << names.len())
+        .map(|mask| {
+            names
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, name)| name.clone())
+                .collect()
+        })
+        .collect()
+}
+
+/// `(test name, result)` for every line like `test features::cfg_macro ... ok` (doctests are skipped).
+fn test_results(stdout: &str) -> Vec<(&str, &str)> {
+    stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("test ")?.split_once(" ... "))
+        .filter(|(name, _)| !name.contains(" - "))
+        .map(|(name, result)| (name, result.split(',').next().unwrap_or(result)))
+        .collect()
+}
+
+/// One line per lesson (module) with the number of tests that ran and were ignored.
+fn lessons(results: &[(&str, &str)]) -> String {
+    let mut lessons: Vec<(&str, usize, usize)> = Vec::new();
+
+    for (name, result) in results {
+        let lesson = name.split("::").next().unwrap_or(name);
+
+        let index = match lessons
+            .iter()
+            .position(|(existing, _, _)| *existing == lesson)
+        {
+            Some(index) => index,
+            None => {
+                lessons.push((lesson, 0, 0));
+                lessons.len() - 1
+            }
+        };
+
+        if *result == "ignored" {
+            lessons[index].2 += 1;
+        } else {
+            lessons[index].1 += 1;
+        }
+    }
+
+    lessons.sort();
+
+    lessons
+        .iter()
+        .map(|(lesson, ran, ignored)| match ignored {
+            0 => format!("      {lesson}: {ran}"),
+            _ => format!("      {lesson}: {ran} ({ignored} ignored)"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn quizzes(root: &Path) -> Outcome {
+    match quiz::verify(root, &target_dir(root)) {
+        Ok(count) => {
+            println!("{count} answers verified");
+            Outcome::Passed
+        }
+        Err(mistakes) => {
+            for mistake in &mistakes {
+                println!("{mistake}");
+            }
+
+            Outcome::Failed(format!("{} wrong answers", mistakes.len()))
+        }
+    }
+}
+
+/// The site is generated from the sources alone, nothing has to be compiled.
+fn book(root: &Path) -> Outcome {
+    match book::build(root, &target_dir(root)) {
+        Ok(index) => {
+            println!("open {}", index.display());
+            Outcome::Passed
+        }
+        Err(error) => Outcome::Failed(error),
+    }
+}
+
+fn format_summary(summary: &[(&str, Outcome, Duration)]) -> String {
+    let mut lines = vec!["summary:".to_string()];
+
+    for (name, outcome, duration) in summary {
+        let (status, detail) = match outcome {
+            Outcome::Passed => ("ok", String::new()),
+            Outcome::Failed(reason) => ("FAILED", format!(" ({reason})")),
+            Outcome::Skipped(reason) => ("skipped", format!(" ({reason})")),
+        };
+
+        lines.push(format!(
+            "  {name:<8} {status:<8} {:>6.1}s{detail}",
+            duration.as_secs_f64()
+        ));
+    }
+
+    lines.join("\n")
+}
@@ -0,0 +1,408 @@
+//! Quizzes for the lessons: `quizzes/<lesson>.toml` belongs to `snippets/src/<lesson>.rs`.
+//!
+//! A question shows a piece of code and asks one of:
+//! - `compiles`: does this compile? (`answer = true` or `false`)
+//! - `prints`: what does this print? (`answer` is the output)
+//! - `error_code`: which error does the compiler report? (`answer` is one of the `choices`, e.g. `"E0382"`)
+//!
+//! The answers are not trusted: the `quizzes` step compiles (and runs) every piece of code and fails if an answer
+//! doesn't match what the compiler and the program actually do, e.g. after a new Rust version accepts code it used to reject.
+//!
+//! `cargo xtask quiz` asks the questions of all quizzes, `cargo xtask quiz references` only those of one lesson.
+
+use std::io::{BufRead, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Quiz {
+    #[serde(rename = "question")]
+    questions: Vec<Question>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Question {
+    code: String,
+    #[serde(flatten)]
+    kind: Kind,
+    #[serde(default)]
+    explanation: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Kind {
+    Compiles {
+        answer: bool,
+    },
+    Prints {
+        answer: String,
+    },
+    ErrorCode {
+        choices: Vec<String>,
+        answer: String,
+    },
+}
+
+/// What happened when the code was compiled and run.
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    /// The code compiled, with the output of the program.
+    Ran(String),
+    /// The code didn't compile, with the first error code (`E0382`) if the error has one.
+    Rejected(Option<String>),
+}
+
+impl Question {
+    /// The code as a program, code without `fn main` is its body.
+    fn program(&self) -> String {
+        if self.code.contains("fn main") {
+            self.code.clone()
+        } else {
+            let body: Vec<String> = self
+                .code
+                .lines()
+                .map(|line| format!("    {line}"))
+                .collect();
+            format!("fn main() {{\n{}\n}}\n", body.join("\n"))
+        }
+    }
+
+    /// The mistake in the answer key, if it contradicts the outcome.
+    fn check(&self, outcome: &Outcome) -> Result<(), String> {
+        match (&self.kind, outcome) {
+            (Kind::Compiles { answer }, Outcome::Ran(_)) if !answer => {
+                Err("the answer is `does not compile`, but it compiles".to_string())
+            }
+            (Kind::Compiles { answer }, Outcome::Rejected(_)) if *answer => {
+                Err("the answer is `compiles`, but it doesn't".to_string())
+            }
+            (Kind::Compiles { .. }, _) => Ok(()),
+            (Kind::Prints { answer }, Outcome::Ran(output)) if output.trim() == answer.trim() => {
+                Ok(())
+            }
+            (Kind::Prints { answer }, Outcome::Ran(output)) => Err(format!(
+                "the answer is `{}`, but it prints `{}`",
+                answer.trim(),
+                output.trim()
+            )),
+            (Kind::Prints { .. }, Outcome::Rejected(_)) => {
+                Err("it doesn't compile, so it prints nothing".to_string())
+            }
+            (Kind::ErrorCode { choices, answer }, _) if !choices.contains(answer) => {
+                Err(format!("the answer `{answer}` is not one of the choices"))
+            }
+            (Kind::ErrorCode { answer, .. }, Outcome::Rejected(Some(code))) if code == answer => {
+                Ok(())
+            }
+            (Kind::ErrorCode { answer, .. }, Outcome::Rejected(code)) => Err(format!(
+                "the answer is `{answer}`, but the compiler reports {}",
+                code.as_deref().unwrap_or("an error without a code")
+            )),
+            (Kind::ErrorCode { answer, .. }, Outcome::Ran(_)) => {
+                Err(format!("the answer is `{answer}`, but it compiles"))
+            }
+        }
+    }
+
+    /// Whether the reply of the reader is the right answer.
+    fn is_correct(&self, reply: &str) -> bool {
+        let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let reply = reply.trim();
+
+        match &self.kind {
+            Kind::Compiles { answer } => {
+                matches!(reply, "y" | "yes") == *answer && matches!(reply, "y" | "yes" | "n" | "no")
+            }
+            Kind::Prints { answer } => normalize(reply) == normalize(answer),
+            Kind::ErrorCode { choices, answer } => {
+                let chosen = reply
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| choices.get(number.wrapping_sub(1)));
+
+                chosen.map_or(reply.eq_ignore_ascii_case(answer), |choice| {
+                    choice == answer
+                })
+            }
+        }
+    }
+}
+
+/// The first `error[E....]` in the output of rustc.
+fn error_code(stderr: &str) -> Option<String> {
+    let start = stderr.find("error[E")? + "error[".len();
+    let end = start + stderr[start..].find(']')?;
+
+    Some(stderr[start..end].to_string())
+}
+
+/// Compiles the program with rustc and runs it.
+fn evaluate(program: &str, build: &Path, name: &str) -> Result<Outcome, String> {
+    let source = build.join(format!("{name}.rs"));
+    let executable = build.join(name);
+
+    std::fs::write(&source, program)
+        .map_err(|error| format!("{} could not be written: {error}", source.display()))?;
+
+    let compiled = Command::new(std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+        .args([
+            "--edition",
+            "2021",
+            "-A",
+            "warnings",
+            "--crate-name",
+            "quiz",
+        ])
+        .arg(&source)
+        .arg("-o")
+        .arg(&executable)
+        .output()
+        .map_err(|error| format!("rustc could not be started: {error}"))?;
+
+    if !compiled.status.success() {
+        return Ok(Outcome::Rejected(error_code(&String::from_utf8_lossy(
+            &compiled.stderr,
+        ))));
+    }
+
+    let ran = Command::new(&executable)
+        .output()
+        .map_err(|error| format!("{} could not be started: {error}", executable.display()))?;
+
+    Ok(Outcome::Ran(
+        String::from_utf8_lossy(&ran.stdout).into_owned(),
+    ))
+}
+
+/// The quizzes in `quizzes/` by lesson name, sorted.
+fn load(root: &Path) -> Result<Vec<(String, Quiz)>, String> {
+    let directory = root.join("quizzes");
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&directory)
+        .map_err(|error| format!("{} could not be read: {error}", directory.display()))?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .collect();
+
+    files.sort();
+
+    files
+        .iter()
+        .map(|path| {
+            let lesson = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+
+            let text = std::fs::read_to_string(path)
+                .map_err(|error| format!("{} could not be read: {error}", path.display()))?;
+            let quiz =
+                toml::from_str(&text).map_err(|error| format!("quizzes/{lesson}.toml: {error}"))?;
+
+            Ok((lesson, quiz))
+        })
+        .collect()
+}
+
+/// Checks the answers of every quiz against the compiler and every quiz against its lesson.
+pub fn verify(root: &Path, target: &Path) -> Result<usize, Vec<String>> {
+    let quizzes = load(root).map_err(|error| vec![error])?;
+
+    let build = target.join("quizzes");
+    std::fs::create_dir_all(&build)
+        .map_err(|error| vec![format!("{} could not be created: {error}", build.display())])?;
+
+    let mut mistakes = Vec::new();
+    let mut count = 0;
+
+    for (lesson, quiz) in &quizzes {
+        if !root
+            .join("snippets")
+            .join("src")
+            .join(format!("{lesson}.rs"))
+            .is_file()
+        {
+            mistakes.push(format!(
+                "quizzes/{lesson}.toml: there is no lesson snippets/src/{lesson}.rs"
+            ));
+        }
+
+        for (index, question) in quiz.questions.iter().enumerate() {
+            let name = format!("{lesson}_{}", index + 1);
+
+            match evaluate(&question.program(), &build, &name) {
+                Ok(outcome) => {
+                    if let Err(mistake) = question.check(&outcome) {
+                        mistakes.push(format!(
+                            "quizzes/{lesson}.toml question {}: {mistake}",
+                            index + 1
+                        ));
+                    }
+                }
+                Err(error) => mistakes.push(error),
+            }
+
+            count += 1;
+        }
+    }
+
+    if mistakes.is_empty() {
+        Ok(count)
+    } else {
+        Err(mistakes)
+    }
+}
+
+/// Asks the questions on the terminal and prints the score.
+pub fn main(root: &Path, args: &[String]) -> ExitCode {
+    let quizzes = match load(root) {
+        Ok(quizzes) => quizzes,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let selected: Vec<&(String, Quiz)> = quizzes
+        .iter()
+        .filter(|(lesson, _)| args.is_empty() || args.contains(lesson))
+        .collect();
+
+    if selected.is_empty() {
+        let lessons: Vec<&str> = quizzes.iter().map(|(lesson, _)| lesson.as_str()).collect();
+        eprintln!(
+            "no quiz for {args:?}, expected one of: {}",
+            lessons.join(", ")
+        );
+        return ExitCode::FAILURE;
+    }
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    let mut correct = 0;
+    let mut asked = 0;
+
+    for (lesson, quiz) in selected {
+        println!("\n==> {lesson} (snippets/src/{lesson}.rs)");
+
+        for question in &quiz.questions {
+            println!("\n{}", question.code.trim_end());
+
+            match &question.kind {
+                Kind::Compiles { .. } => print!("\nDoes this compile? [y/n] "),
+                Kind::Prints { .. } => print!("\nWhat does this print? "),
+                Kind::ErrorCode { choices, .. } => {
+                    println!("\nWhich error does the compiler report?");
+
+                    for (number, choice) in choices.iter().enumerate() {
+                        println!("  {}) {choice}", number + 1);
+                    }
+
+                    print!("> ");
+                }
+            }
+
+            let _ = std::io::stdout().flush();
+
+            let Some(Ok(reply)) = lines.next() else {
+                break;
+            };
+
+            asked += 1;
+
+            if question.is_correct(&reply) {
+                correct += 1;
+                println!("Correct!");
+            } else {
+                let answer = match &question.kind {
+                    Kind::Compiles { answer: true } => "it compiles".to_string(),
+                    Kind::Compiles { answer: false } => "it doesn't compile".to_string(),
+                    Kind::Prints { answer } | Kind::ErrorCode { answer, .. } => {
+                        answer.trim().to_string()
+                    }
+                };
+
+                println!("Wrong, the answer is: {answer}");
+            }
+
+            if !question.explanation.is_empty() {
+                println!("{}", question.explanation);
+            }
+        }
+    }
+
+    println!("\n{correct}/{asked} correct");
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(text: &str) -> Question {
+        let quiz: Quiz = toml::from_str(text).unwrap();
+        quiz.questions.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let compiles =
+            question("[[question]]\nkind = \"compiles\"\ncode = \"let x = 1;\"\nanswer = true\n");
+
+        assert_eq!(compiles.kind, Kind::Compiles { answer: true });
+        assert_eq!(compiles.program(), "fn main() {\n    let x = 1;\n}\n");
+        assert_eq!(compiles.explanation, "");
+
+        let error = question(
+            "[[question]]\nkind = \"error_code\"\ncode = \"fn main() {}\"\nchoices = [\"E0382\", \"E0499\"]\nanswer = \"E0499\"\n",
+        );
+
+        assert_eq!(error.program(), "fn main() {}");
+        assert!(error.is_correct("2"));
+        assert!(error.is_correct("e0499"));
+        assert!(!error.is_correct("1"));
+
+        let unknown = toml::from_str::<Quiz>("[[question]]\nkind = \"guess\"\ncode = \"\"\n");
+
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn answer_key() {
+        let prints =
+            question("[[question]]\nkind = \"prints\"\ncode = \"\"\nanswer = \"\"\"\n1\n2\"\"\"\n");
+
+        assert_eq!(prints.check(&Outcome::Ran("1\n2\n".to_string())), Ok(()));
+        assert!(prints.check(&Outcome::Ran("1\n".to_string())).is_err());
+        assert!(prints.check(&Outcome::Rejected(None)).is_err());
+        assert!(prints.is_correct("1 2"));
+
+        let error = question(
+            "[[question]]\nkind = \"error_code\"\ncode = \"\"\nchoices = [\"E0382\"]\nanswer = \"E0382\"\n",
+        );
+
+        assert_eq!(
+            error.check(&Outcome::Rejected(Some("E0382".to_string()))),
+            Ok(())
+        );
+        assert!(error
+            .check(&Outcome::Rejected(Some("E0499".to_string())))
+            .is_err());
+        assert!(error.check(&Outcome::Ran(String::new())).is_err());
+
+        assert_eq!(
+            error_code("warning: unused\nerror[E0382]: borrow of moved value: `name`\n"),
+            Some("E0382".to_string())
+        );
+    }
+}